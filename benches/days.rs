@@ -0,0 +1,188 @@
+//! Benchmarks of every day's parser and both parts, over the real input and over generated
+//! inputs ten and one hundred times its size.
+//!
+//! Run with `cargo bench`, optionally followed by a filter on the benchmark names, e.g.,
+//! `cargo bench -- day_08/`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use aoc_2022_rust::{
+    day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09,
+    generate::{self, Options},
+};
+
+const SAMPLES: usize = 10;
+/// Iterations are batched until a single sample takes at least this long
+const SAMPLE_TIME: Duration = Duration::from_millis(10);
+
+fn main() {
+    let bencher = Bencher {
+        filter: std::env::args().skip(1).find(|arg| !arg.starts_with("--")),
+    };
+    // The size of each real input, in the units used by its generator:
+    let real_sizes = [
+        (1, 249),
+        (2, 2_500),
+        (3, 300),
+        (4, 1_000),
+        (5, 503),
+        (6, 4_096),
+        (7, 176),
+        (8, 99),
+        (9, 2_000),
+    ];
+    for (day, real_size) in real_sizes {
+        for (label, input) in inputs(day, real_size) {
+            let input = input.as_str();
+            let name = |bench: &str| format!("day_{day:02}/{bench}/{label}");
+            match day {
+                1 => {
+                    bencher.run(&name("parse"), || {
+                        input.lines().map(day_01::parse_line).count()
+                    });
+                    bencher.run(&name("part_1"), || day_01::part_1(input.as_bytes()));
+                    bencher.run(&name("part_2"), || day_01::part_2(input.as_bytes()));
+                }
+                2 => {
+                    bencher.run(&name("parse"), || {
+                        input
+                            .lines()
+                            .map(day_02::parse_round::<day_02::Play>)
+                            .count()
+                    });
+                    bencher.run(&name("part_1"), || day_02::part_1(input.as_bytes()));
+                    bencher.run(&name("part_2"), || day_02::part_2(input.as_bytes()));
+                    bencher.run(&name("part_1_table"), || {
+                        day_02::score_bytes(input.as_bytes(), day_02::Strategy::Weapon)
+                    });
+                    bencher.run(&name("part_2_table"), || {
+                        day_02::score_bytes(input.as_bytes(), day_02::Strategy::Outcome)
+                    });
+                }
+                3 => {
+                    bencher.run(&name("parse"), || {
+                        input.lines().map(day_03::parse_rucksack).count()
+                    });
+                    bencher.run(&name("part_1"), || day_03::part_1(input.as_bytes()));
+                    bencher.run(&name("part_2"), || day_03::part_2(input.as_bytes()));
+                    bencher.run(&name("part_1_naive"), || {
+                        day_03::part_1_naive(input.as_bytes())
+                    });
+                    bencher.run(&name("part_2_naive"), || {
+                        day_03::part_2_naive(input.as_bytes())
+                    });
+                }
+                4 => {
+                    bencher.run(&name("parse"), || {
+                        input.lines().map(str::parse::<day_04::RangePair>).count()
+                    });
+                    bencher.run(&name("part_1"), || day_04::part_1(input.as_bytes()));
+                    bencher.run(&name("part_2"), || day_04::part_2(input.as_bytes()));
+                }
+                5 => {
+                    bencher.run(&name("parse"), || {
+                        day_05::CargoBay::new_9000().load(input.as_bytes())
+                    });
+                    bencher.run(&name("part_1"), || day_05::part_1(input.as_bytes()));
+                    bencher.run(&name("part_2"), || day_05::part_2(input.as_bytes()));
+                }
+                6 => {
+                    // The signal is used as-is, so there is no parser to measure
+                    bencher.run(&name("part_1"), || day_06::part_1(input.as_bytes()));
+                    bencher.run(&name("part_2"), || day_06::part_2(input.as_bytes()));
+                }
+                7 => {
+                    bencher.run(&name("parse"), || {
+                        input.lines().map(day_07::parse_line).count()
+                    });
+                    bencher.run(&name("part_1"), || day_07::part_1(input.as_bytes()));
+                    bencher.run(&name("part_2"), || day_07::part_2(input.as_bytes()));
+                }
+                8 => {
+                    bencher.run(&name("parse"), || day_08::parse(input.as_bytes()));
+                    bencher.run(&name("part_1"), || day_08::part_1(input.as_bytes()));
+                    bencher.run(&name("part_2"), || day_08::part_2(input.as_bytes()));
+                    let tp = day_08::parse(input.as_bytes()).expect("parses forest");
+                    bencher.run(&name("row_wise_forward"), || tp.row_wise_forward());
+                    bencher.run(&name("row_wise_backward"), || tp.row_wise_backward());
+                    bencher.run(&name("col_wise_forward"), || tp.col_wise_forward());
+                    bencher.run(&name("col_wise_backward"), || tp.col_wise_backward());
+                    bencher.run(&name("count_visible"), || day_08::count_visible(&tp));
+                    bencher.run(&name("score"), || tp.score());
+                }
+                9 => {
+                    bencher.run(&name("parse"), || {
+                        input.lines().map(str::parse::<day_09::Instruction>).count()
+                    });
+                    bencher.run(&name("part_1"), || day_09::part_1(input.as_bytes()));
+                    bencher.run(&name("part_2"), || day_09::part_2(input.as_bytes()));
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+/// The real input for the `day`, followed by generated inputs ten and one hundred times larger
+fn inputs(day: u8, real_size: usize) -> Vec<(&'static str, String)> {
+    let real = std::fs::read_to_string(format!("input/day_{day:02}.txt")).expect("reads input");
+    let mut inputs = vec![("real", real)];
+    for (label, scale) in [("10x", 10.0), ("100x", 100.0)] {
+        // Forests are square, so scale the side rather than the number of trees:
+        let size = if day == 8 {
+            (real_size as f64 * f64::sqrt(scale)) as usize
+        } else {
+            (real_size as f64 * scale) as usize
+        };
+        let options = Options {
+            seed: u64::from(day),
+            size,
+            // Put the marker at the very end, so the whole signal is searched:
+            offset: Some(size - 14),
+        };
+        inputs.push((
+            label,
+            generate::generate(day, &options).expect("generates input"),
+        ));
+    }
+    inputs
+}
+
+struct Bencher {
+    filter: Option<String>,
+}
+
+impl Bencher {
+    fn run<T>(&self, name: &str, mut f: impl FnMut() -> T) {
+        if self
+            .filter
+            .as_ref()
+            .is_some_and(|filter| !name.contains(filter))
+        {
+            return;
+        }
+        let mut sample = |iters: u32| {
+            let start = Instant::now();
+            for _ in 0..iters {
+                black_box(f());
+            }
+            start.elapsed()
+        };
+        let mut iters = 1;
+        while sample(iters) < SAMPLE_TIME {
+            iters *= 2;
+        }
+        let mut samples = (0..SAMPLES)
+            .map(|_| sample(iters) / iters)
+            .collect::<Vec<Duration>>();
+        samples.sort();
+        println!(
+            "{name:<36} {median:>12.3?}/iter (min {min:.3?}, {iters} iters/sample)",
+            median = samples[SAMPLES / 2],
+            min = samples[0],
+        );
+    }
+}
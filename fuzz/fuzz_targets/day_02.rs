@@ -0,0 +1,11 @@
+#![no_main]
+
+use aoc_2022_rust::day_02::{self, Outcome, Play};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for line in input.lines() {
+        let _ = day_02::parse_round::<Play>(line);
+        let _ = day_02::parse_round::<Outcome>(line);
+    }
+});
@@ -0,0 +1,14 @@
+#![no_main]
+
+use aoc_2022_rust::day_03;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let lines = input.lines().collect::<Vec<&str>>();
+    for line in &lines {
+        let _ = day_03::parse_rucksack(line);
+    }
+    for group in lines.chunks(3) {
+        let _ = day_03::process_group(group);
+    }
+});
@@ -0,0 +1,14 @@
+#![no_main]
+
+use aoc_2022_rust::day_05::CargoBay;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    for mut cargo_bay in [CargoBay::new_9000(), CargoBay::new_9001()] {
+        if let Ok(instructions) = cargo_bay.load(data) {
+            for instruction in instructions {
+                let _ = cargo_bay.move_crate(instruction);
+            }
+        }
+    }
+});
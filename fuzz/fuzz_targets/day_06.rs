@@ -0,0 +1,9 @@
+#![no_main]
+
+use aoc_2022_rust::day_06;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    day_06::find_marker::<4, _>(data);
+    day_06::find_marker::<14, _>(data);
+});
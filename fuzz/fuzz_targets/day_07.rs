@@ -0,0 +1,10 @@
+#![no_main]
+
+use aoc_2022_rust::day_07;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for line in input.lines() {
+        let _ = day_07::parse_line(line);
+    }
+});
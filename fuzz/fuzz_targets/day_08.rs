@@ -0,0 +1,8 @@
+#![no_main]
+
+use aoc_2022_rust::day_08;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = day_08::parse(data);
+});
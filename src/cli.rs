@@ -0,0 +1,47 @@
+use std::{collections::HashMap, str::FromStr};
+
+/// Command line flags of the form `--name value`
+#[derive(Debug, Default)]
+pub struct Flags(HashMap<String, String>);
+
+impl Flags {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut flags = HashMap::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .ok_or_else(|| format!("expected a flag, found: {arg}"))?;
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for flag --{name}"))?;
+            flags.insert(name.to_owned(), value);
+        }
+        Ok(Self(flags))
+    }
+
+    /// Get the value of an optional flag
+    pub fn get<T>(&self, name: &str) -> Result<Option<T>, String>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.0
+            .get(name)
+            .map(|v| {
+                v.parse::<T>()
+                    .map_err(|e| format!("invalid value for --{name}: {e}"))
+            })
+            .transpose()
+    }
+
+    /// Get the value of a flag that must be provided
+    pub fn require<T>(&self, name: &str) -> Result<T, String>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.get(name)?
+            .ok_or_else(|| format!("missing required flag --{name}"))
+    }
+}
@@ -1,39 +1,634 @@
-use std::collections::BinaryHeap;
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    io::BufRead,
+};
 
-use crate::utils::get_lines;
+use crate::utils::get_buf_reader;
 
 pub fn answer_part_1() -> usize {
-    let mut most = 0;
-    let mut current = 0;
-    for line in get_lines("input/day_01.txt") {
-        match line {
-            Ok(l) => {
-                if l.is_empty() {
-                    if current > most {
-                        most = current
-                    }
-                    current = 0;
-                } else {
-                    current += l.parse::<usize>().expect("unparseable line!")
+    part_1(get_buf_reader("input/day_01.txt"))
+}
+
+pub fn answer_part_2() -> usize {
+    part_2(get_buf_reader("input/day_01.txt"))
+}
+
+pub fn part_1<R: BufRead>(input: R) -> usize {
+    top_elves(input, 1)
+        .expect("parses calorie list")
+        .iter()
+        .map(|elf| elf.total)
+        .sum()
+}
+
+pub fn part_2<R: BufRead>(input: R) -> usize {
+    top_elves(input, 3)
+        .expect("parses calorie list")
+        .iter()
+        .map(|elf| elf.total)
+        .sum()
+}
+
+/// An elf and the food items they are carrying
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    /// The position of the elf in the calorie list, starting from zero
+    pub index: usize,
+    pub total: usize,
+    pub items: Vec<usize>,
+}
+
+/// Elves are ordered by the calories they carry; of two elves carrying the same amount, the one
+/// that comes first in the list is considered greater
+impl Ord for Elf {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total
+            .cmp(&other.total)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for Elf {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Elf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let items = self
+            .items
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join(", ");
+        write!(f, "elf {}: {} calories [{items}]", self.index, self.total)
+    }
+}
+
+/// Find the `k` elves carrying the most calories, from most to least
+///
+/// Only the `k` best elves seen so far are kept in memory, in a min-heap whose smallest elf is
+/// evicted whenever a better one comes along.
+pub fn top_elves<R: BufRead>(input: R, k: usize) -> Result<Vec<Elf>, String> {
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for elf in Elves::new(input) {
+        heap.push(Reverse(elf?));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+    Ok(heap.into_sorted_vec().into_iter().map(|r| r.0).collect())
+}
+
+/// Streams the elves from a calorie list, one group of lines at a time
+///
+/// The last elf does not need to be followed by a blank line, and runs of several blank lines
+/// do not produce elves carrying nothing.
+pub struct Elves<R> {
+    lines: std::io::Lines<R>,
+    index: usize,
+}
+
+impl<R: BufRead> Elves<R> {
+    pub fn new(input: R) -> Self {
+        Self {
+            lines: input.lines(),
+            index: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Elves<R> {
+    type Item = Result<Elf, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut items = Vec::new();
+        for line in self.lines.by_ref() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(format!("failed to read line: {e}"))),
+            };
+            match parse_line(&line) {
+                Ok(Some(calories)) => items.push(calories),
+                Ok(None) if items.is_empty() => continue,
+                Ok(None) => break,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        if items.is_empty() {
+            return None;
+        }
+        let elf = Elf {
+            index: self.index,
+            total: items.iter().sum(),
+            items,
+        };
+        self.index += 1;
+        Some(Ok(elf))
+    }
+}
+
+/// How many of the largest totals [`CalorieStats`] keeps, enough for both parts
+const TOP: usize = 3;
+
+/// Summary statistics over the calories carried by each elf
+#[derive(Debug, Default)]
+pub struct CalorieStats {
+    count: usize,
+    total: usize,
+    /// The largest totals, from most to least
+    top: Vec<usize>,
+    /// The number of items carried in all, and the fewest and most carried by one elf
+    items: usize,
+    item_range: Option<(usize, usize)>,
+    /// Every total from least to most, for the median, percentiles and histogram
+    sorted: Vec<usize>,
+}
+
+impl CalorieStats {
+    /// Gather the statistics in a single pass over the calorie list
+    ///
+    /// Everything but the order statistics is kept as a running aggregate, but the median,
+    /// percentiles and histogram need every total, so those are buffered and sorted once.
+    pub fn from_reader<R: BufRead>(input: R) -> Result<Self, String> {
+        let mut stats = Self::default();
+        for elf in Elves::new(input) {
+            let elf = elf?;
+            stats.count += 1;
+            stats.total += elf.total;
+            let rank = stats.top.partition_point(|&top| top >= elf.total);
+            if rank < TOP {
+                stats.top.insert(rank, elf.total);
+                stats.top.truncate(TOP);
+            }
+            let n = elf.items.len();
+            stats.items += n;
+            stats.item_range = Some(
+                stats
+                    .item_range
+                    .map_or((n, n), |(fewest, most)| (fewest.min(n), most.max(n))),
+            );
+            stats.sorted.push(elf.total);
+        }
+        stats.sorted.sort_unstable();
+        Ok(stats)
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// The largest totals, from most to least, which give the answers to both parts
+    pub fn top(&self) -> &[usize] {
+        &self.top
+    }
+
+    pub fn items(&self) -> usize {
+        self.items
+    }
+
+    /// The fewest and most items carried by one elf
+    pub fn item_range(&self) -> Option<(usize, usize)> {
+        self.item_range
+    }
+
+    pub fn max(&self) -> Option<usize> {
+        self.top.first().copied()
+    }
+
+    pub fn min(&self) -> Option<usize> {
+        self.sorted.first().copied()
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count() > 0).then(|| self.total() as f64 / self.count() as f64)
+    }
+
+    pub fn median(&self) -> Option<f64> {
+        let n = self.count();
+        match n {
+            0 => None,
+            _ if n % 2 == 1 => Some(self.sorted[n / 2] as f64),
+            _ => Some((self.sorted[n / 2 - 1] + self.sorted[n / 2]) as f64 / 2.0),
+        }
+    }
+
+    /// The nearest-rank `p`th percentile of the totals, for `p` in `0.0..=100.0`
+    pub fn percentile(&self, p: f64) -> Option<usize> {
+        if self.sorted.is_empty() || !(0.0..=100.0).contains(&p) {
+            return None;
+        }
+        let rank = (p / 100.0 * self.count() as f64).ceil() as usize;
+        Some(self.sorted[rank.max(1) - 1])
+    }
+
+    /// Draw a histogram of the totals split into `bins` equal-width bins, with the largest bin
+    /// drawn `width` characters wide
+    pub fn histogram(&self, bins: usize, width: usize) -> String {
+        let (Some(min), Some(max)) = (self.min(), self.max()) else {
+            return String::new();
+        };
+        // Rounding the width up can leave fewer bins than asked for, but never an empty one past
+        // the largest total:
+        let bin_width = (max - min + 1).div_ceil(bins.max(1));
+        let mut counts = vec![0; (max - min) / bin_width + 1];
+        for total in &self.sorted {
+            counts[(total - min) / bin_width] += 1;
+        }
+        let tallest = counts.iter().copied().max().unwrap_or(0).max(1);
+        let mut out = String::new();
+        for (i, count) in counts.iter().enumerate() {
+            let lo = min + i * bin_width;
+            let bar = "#".repeat(count * width / tallest);
+            out.push_str(&format!(
+                "{lo:>8} - {hi:>8} | {bar} {count}\n",
+                hi = (lo + bin_width - 1).min(max)
+            ));
+        }
+        out
+    }
+}
+
+impl std::fmt::Display for CalorieStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "elves:   {}", self.count())?;
+        writeln!(f, "total:   {}", self.total())?;
+        if let (Some(min), Some(max), Some(mean), Some(median)) =
+            (self.min(), self.max(), self.mean(), self.median())
+        {
+            writeln!(f, "min:     {min}")?;
+            writeln!(f, "max:     {max}")?;
+            writeln!(
+                f,
+                "top {}:   {}",
+                self.top.len(),
+                self.top.iter().sum::<usize>()
+            )?;
+            writeln!(f, "mean:    {mean:.1}")?;
+            writeln!(f, "median:  {median:.1}")?;
+            for p in [10.0, 25.0, 75.0, 90.0, 99.0] {
+                if let Some(value) = self.percentile(p) {
+                    writeln!(f, "p{p:<7}{value}")?;
                 }
             }
-            Err(e) => panic!("failed to read line: {e}"),
         }
+        if let Some((fewest, most)) = self.item_range {
+            writeln!(
+                f,
+                "items:   {} in total, {fewest} to {most} per elf ({:.1} on average)",
+                self.items,
+                self.items as f64 / self.count() as f64
+            )?;
+        }
+        Ok(())
     }
-    most
 }
 
-pub fn answer_part_2() -> usize {
+/// How to split the elves into teams
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// Use branch-and-bound for small lists, and the better heuristic otherwise
+    Auto,
+    /// Branch-and-bound search for an optimal split, falling back to Karmarkar-Karp when the
+    /// search takes too long
+    Exact,
+    /// Hand each elf, from most to least calories, to the least loaded team
+    Greedy,
+    /// The multiway Karmarkar-Karp largest differencing method
+    KarmarkarKarp,
+}
+
+impl std::str::FromStr for Method {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "exact" => Ok(Self::Exact),
+            "greedy" => Ok(Self::Greedy),
+            "kk" => Ok(Self::KarmarkarKarp),
+            _ => Err(format!(
+                "unknown method {s:?}, expected auto, exact, greedy or kk"
+            )),
+        }
+    }
+}
+
+/// The largest number of elves that [`Method::Auto`] will search exhaustively
+const EXACT_LIMIT: usize = 20;
+
+/// The most splits that branch-and-bound will visit before giving up on proving optimality
+const NODE_BUDGET: usize = 1_000_000;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Team {
+    pub load: usize,
+    /// The indices of the elves in the team
+    pub elves: Vec<usize>,
+}
+
+#[derive(Debug)]
+pub struct Partition {
+    /// Teams from most to least loaded
+    pub teams: Vec<Team>,
+    /// No split can have a largest load below this
+    pub lower_bound: usize,
+    /// Whether the split is known to minimise the largest load
+    pub optimal: bool,
+}
+
+impl Partition {
+    pub fn max_load(&self) -> usize {
+        self.teams.first().map_or(0, |t| t.load)
+    }
+
+    /// How far the largest load is above the lower bound
+    pub fn gap(&self) -> usize {
+        self.max_load() - self.lower_bound
+    }
+}
+
+impl std::fmt::Display for Partition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, team) in self.teams.iter().enumerate() {
+            let elves = team
+                .elves
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(", ");
+            writeln!(f, "team {i}: {} calories, elves [{elves}]", team.load)?;
+        }
+        write!(
+            f,
+            "largest load {}, lower bound {}, gap {}{}",
+            self.max_load(),
+            self.lower_bound,
+            self.gap(),
+            if self.optimal { " (optimal)" } else { "" }
+        )
+    }
+}
+
+/// Split the elves, given by their calorie totals, into `n` teams so that the largest total
+/// carried by any one team is as small as possible
+pub fn partition(totals: &[usize], n: usize, method: Method) -> Result<Partition, String> {
+    if n == 0 {
+        return Err("cannot split elves into zero teams".to_string());
+    }
+    let sum = totals.iter().sum::<usize>();
+    let lower_bound = sum
+        .div_ceil(n)
+        .max(totals.iter().copied().max().unwrap_or(0));
+    let (mut teams, proven) = match method {
+        Method::Greedy => (greedy(totals, n), false),
+        Method::KarmarkarKarp => (karmarkar_karp(totals, n), false),
+        Method::Exact => branch_and_bound(totals, n),
+        Method::Auto if totals.len() <= EXACT_LIMIT => branch_and_bound(totals, n),
+        Method::Auto => (better(greedy(totals, n), karmarkar_karp(totals, n)), false),
+    };
+    for team in teams.iter_mut() {
+        team.elves.sort_unstable();
+    }
+    teams.sort_by(|a, b| b.load.cmp(&a.load).then_with(|| a.elves.cmp(&b.elves)));
+    let optimal = proven || max_load(&teams) == lower_bound;
+    Ok(Partition {
+        teams,
+        lower_bound,
+        optimal,
+    })
+}
+
+fn max_load(teams: &[Team]) -> usize {
+    teams.iter().map(|t| t.load).max().unwrap_or(0)
+}
+
+/// Whichever split has the smaller largest load, preferring `a` on a tie
+fn better(a: Vec<Team>, b: Vec<Team>) -> Vec<Team> {
+    if max_load(&a) <= max_load(&b) {
+        a
+    } else {
+        b
+    }
+}
+
+/// Elf indices ordered from most to least calories
+fn by_total_desc(totals: &[usize]) -> Vec<usize> {
+    let mut order = (0..totals.len()).collect::<Vec<usize>>();
+    order.sort_by(|a, b| totals[*b].cmp(&totals[*a]));
+    order
+}
+
+fn greedy(totals: &[usize], n: usize) -> Vec<Team> {
+    let mut teams = vec![Team::default(); n];
+    // A min-heap of (load, team):
+    let mut heap = (0..n).map(|t| Reverse((0, t))).collect::<BinaryHeap<_>>();
+    for elf in by_total_desc(totals) {
+        let Reverse((load, t)) = heap.pop().expect("there is at least one team");
+        teams[t].load += totals[elf];
+        teams[t].elves.push(elf);
+        heap.push(Reverse((load + totals[elf], t)));
+    }
+    teams
+}
+
+fn karmarkar_karp(totals: &[usize], n: usize) -> Vec<Team> {
+    // Each partial split has `n` teams, sorted from most to least loaded, and is keyed by the
+    // difference between its most and least loaded teams:
+    let spread = |teams: &Vec<Team>| teams[0].load - teams[n - 1].load;
+    let mut splits = Vec::with_capacity(2 * totals.len());
     let mut heap = BinaryHeap::new();
-    let mut current = 0;
-    for line in get_lines("input/day_01.txt") {
-        let line = line.unwrap();
-        if line.is_empty() {
-            heap.push(current);
-            current = 0;
-        } else {
-            current += line.parse::<usize>().expect("parseable line");
+    for (elf, total) in totals.iter().enumerate() {
+        let mut teams = vec![Team::default(); n];
+        teams[0] = Team {
+            load: *total,
+            elves: vec![elf],
+        };
+        heap.push((spread(&teams), Reverse(splits.len())));
+        splits.push(Some(teams));
+    }
+    while heap.len() > 1 {
+        let (_, Reverse(a)) = heap.pop().unwrap();
+        let (_, Reverse(b)) = heap.pop().unwrap();
+        let (a, b) = (splits[a].take().unwrap(), splits[b].take().unwrap());
+        // Combine the most loaded teams of one with the least loaded of the other:
+        let mut teams = a
+            .into_iter()
+            .zip(b.into_iter().rev())
+            .map(|(mut x, y)| {
+                x.load += y.load;
+                x.elves.extend(y.elves);
+                x
+            })
+            .collect::<Vec<Team>>();
+        teams.sort_by_key(|t| Reverse(t.load));
+        heap.push((spread(&teams), Reverse(splits.len())));
+        splits.push(Some(teams));
+    }
+    heap.pop()
+        .and_then(|(_, Reverse(i))| splits[i].take())
+        .unwrap_or_else(|| vec![Team::default(); n])
+}
+
+/// The best split found, and whether the search finished within [`NODE_BUDGET`] to prove it
+/// optimal; when it didn't, Karmarkar-Karp is used instead unless the search did better
+fn branch_and_bound(totals: &[usize], n: usize) -> (Vec<Team>, bool) {
+    struct Search<'a> {
+        totals: &'a [usize],
+        order: Vec<usize>,
+        lower_bound: usize,
+        loads: Vec<usize>,
+        assignment: Vec<usize>,
+        best_load: usize,
+        best: Vec<usize>,
+        /// How many more splits may be visited
+        budget: usize,
+    }
+
+    impl Search<'_> {
+        /// Returns true once a split meeting the lower bound has been found, or the budget has
+        /// run out
+        fn visit(&mut self, depth: usize) -> bool {
+            if self.budget == 0 {
+                return true;
+            }
+            self.budget -= 1;
+            let Some(&elf) = self.order.get(depth) else {
+                let load = self.loads.iter().copied().max().unwrap_or(0);
+                if load < self.best_load {
+                    self.best_load = load;
+                    self.best.clone_from(&self.assignment);
+                }
+                return self.best_load <= self.lower_bound;
+            };
+            for team in 0..self.loads.len() {
+                // Teams with the same load are interchangeable, so only try the first of them:
+                if self.loads[..team].contains(&self.loads[team]) {
+                    continue;
+                }
+                if self.loads[team] + self.totals[elf] >= self.best_load {
+                    continue;
+                }
+                self.loads[team] += self.totals[elf];
+                self.assignment[elf] = team;
+                let done = self.visit(depth + 1);
+                self.loads[team] -= self.totals[elf];
+                if done {
+                    return true;
+                }
+            }
+            false
         }
     }
-    heap.pop().unwrap() + heap.pop().unwrap() + heap.pop().unwrap()
+
+    // Start from the greedy split, so the search only has to look for something better:
+    let start = greedy(totals, n);
+    let mut best = vec![0; totals.len()];
+    for (t, team) in start.iter().enumerate() {
+        for elf in &team.elves {
+            best[*elf] = t;
+        }
+    }
+    let sum = totals.iter().sum::<usize>();
+    let mut search = Search {
+        totals,
+        order: by_total_desc(totals),
+        lower_bound: sum
+            .div_ceil(n)
+            .max(totals.iter().copied().max().unwrap_or(0)),
+        loads: vec![0; n],
+        assignment: vec![0; totals.len()],
+        best_load: max_load(&start),
+        best,
+        budget: NODE_BUDGET,
+    };
+    if search.best_load > search.lower_bound {
+        search.visit(0);
+    }
+    let mut teams = vec![Team::default(); n];
+    for (elf, t) in search.best.into_iter().enumerate() {
+        teams[t].load += totals[elf];
+        teams[t].elves.push(elf);
+    }
+    if search.budget == 0 {
+        return (better(teams, karmarkar_karp(totals, n)), false);
+    }
+    (teams, true)
+}
+
+/// Parse a line of the calorie list, which is either the calories of a single food item, or
+/// blank to separate one elf's items from the next
+pub fn parse_line(line: &str) -> Result<Option<usize>, String> {
+    if line.is_empty() {
+        return Ok(None);
+    }
+    line.parse::<usize>()
+        .map(Some)
+        .map_err(|e| format!("invalid calories {line:?}: {e}"))
+}
+
+#[test]
+fn test_top_elves() {
+    let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+    let top = top_elves(input.as_bytes(), 3).unwrap();
+    let summary = top.iter().map(|e| (e.index, e.total)).collect::<Vec<_>>();
+    assert_eq!(summary, [(3, 24000), (2, 11000), (4, 10000)]);
+    assert_eq!(top[0].items, [7000, 8000, 9000]);
+    // The last elf counts, even without a trailing blank line:
+    assert_eq!(part_1("1\n\n2".as_bytes()), 2);
+    // Ties go to the elf that comes first:
+    let top = top_elves("5\n\n3\n2\n\n5".as_bytes(), 2).unwrap();
+    assert_eq!(top.iter().map(|e| e.index).collect::<Vec<_>>(), [0, 1]);
+}
+
+#[test]
+fn test_calorie_stats() {
+    let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+    let stats = CalorieStats::from_reader(input.as_bytes()).unwrap();
+    assert_eq!(stats.count(), 5);
+    assert_eq!(stats.total(), 55000);
+    assert_eq!(stats.max(), Some(24000));
+    assert_eq!(stats.mean(), Some(11000.0));
+    assert_eq!(stats.median(), Some(10000.0));
+    assert_eq!(stats.percentile(40.0), Some(6000));
+    assert_eq!(stats.percentile(100.0), Some(24000));
+    assert_eq!(stats.top(), [24000, 11000, 10000]);
+    assert_eq!((stats.items(), stats.item_range()), (10, Some((1, 3))));
+    let histogram = stats.histogram(2, 10);
+    assert_eq!(histogram.lines().count(), 2);
+    assert!(histogram.lines().next().unwrap().ends_with("########## 4"));
+
+    // The 11 values from 10 to 20 fill bins of 3, so only 4 of the 5 bins are needed:
+    let stats = CalorieStats::from_reader("10\n\n12\n\n15\n\n20".as_bytes()).unwrap();
+    let histogram = stats.histogram(5, 10);
+    assert_eq!(histogram.lines().count(), 4);
+    assert_eq!(
+        histogram.lines().last(),
+        Some("      19 -       20 | ##### 1")
+    );
+}
+
+#[test]
+fn test_partition() {
+    let totals = [3, 3, 2, 2, 2];
+    // Greedy hands out 3, 3, 2, 2 evenly, then has to put the last 2 on a team of 5:
+    let greedy = partition(&totals, 2, Method::Greedy).unwrap();
+    assert_eq!((greedy.max_load(), greedy.lower_bound), (7, 6));
+    assert!(!greedy.optimal);
+    let exact = partition(&totals, 2, Method::Exact).unwrap();
+    assert_eq!((exact.max_load(), exact.gap()), (6, 0));
+    assert_eq!(exact.teams[0].elves.len() + exact.teams[1].elves.len(), 5);
+    // Every load is even, so the search can't meet the odd lower bound and runs out of budget:
+    let fallback = partition(&[2; 41], 2, Method::Exact).unwrap();
+    assert_eq!((fallback.max_load(), fallback.lower_bound), (42, 41));
+    assert!(!fallback.optimal);
+    let kk = partition(&totals, 2, Method::KarmarkarKarp).unwrap();
+    assert!(kk.max_load() >= 6);
+    assert_eq!(kk.teams.iter().map(|t| t.load).sum::<usize>(), 12);
 }
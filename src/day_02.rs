@@ -1,32 +1,120 @@
-use crate::utils::get_lines;
+use std::{cmp::Reverse, collections::HashMap, io::BufRead, sync::OnceLock};
+
+use crate::utils::get_buf_reader;
 
 pub fn answer_part_1() -> usize {
-    let mut points = 0;
-    for line in get_lines("input/day_02.txt") {
-        let line = line.expect("reads a line");
-        let theirs = line.chars().nth(0).map(Play::try_from).unwrap().unwrap();
-        let mine = line.chars().nth(2).map(Play::try_from).unwrap().unwrap();
+    part_1(get_buf_reader("input/day_02.txt"))
+}
+
+pub fn answer_part_2() -> usize {
+    part_2(get_buf_reader("input/day_02.txt"))
+}
+
+pub fn part_1<R: BufRead>(input: R) -> usize {
+    score_guide(input, classic(), Strategy::Weapon).expect("parses guide")
+}
 
-        points += mine.score(&theirs) + mine.play_points();
+pub fn part_2<R: BufRead>(input: R) -> usize {
+    score_guide(input, classic(), Strategy::Outcome).expect("parses guide")
+}
+
+/// The puzzle's own game, which [`Play`] is scored under
+fn classic() -> &'static Game {
+    static CLASSIC: OnceLock<Game> = OnceLock::new();
+    CLASSIC.get_or_init(Game::rock_paper_scissors)
+}
+
+/// Parse a round of the strategy guide, i.e., the opponent's play followed by a space and the
+/// second column, which is interpreted as `T`
+pub fn parse_round<T>(line: &str) -> Result<(Play, T), String>
+where
+    T: TryFrom<char, Error = String>,
+{
+    let mut chars = line.chars();
+    match (chars.next(), chars.next(), chars.next(), chars.next()) {
+        (Some(theirs), Some(' '), Some(second), None) => {
+            Ok((Play::try_from(theirs)?, T::try_from(second)?))
+        }
+        _ => Err(format!("invalid round: {line:?}")),
     }
-    points
 }
 
-pub fn answer_part_2() -> usize {
-    let mut points = 0;
-    for line in get_lines("input/day_02.txt") {
-        let line = line.expect("reads a line");
-        let theirs = line.chars().nth(0).map(Play::try_from).unwrap().unwrap();
-        let desired = line.chars().nth(2).map(Outcome::try_from).unwrap().unwrap();
-        let mine = Play::needed_for(&desired, &theirs);
-        points += mine.score(&theirs) + mine.play_points();
+/// Points for every round, indexed by `[their letter - 'A'][second letter - 'X']`
+pub fn score_table(strategy: Strategy) -> [[usize; 3]; 3] {
+    let mut table = [[0; 3]; 3];
+    for (i, theirs) in ['A', 'B', 'C'].into_iter().enumerate() {
+        for (j, second) in ['X', 'Y', 'Z'].into_iter().enumerate() {
+            table[i][j] = classic()
+                .score_round(&format!("{theirs} {second}"), strategy)
+                .expect("guide letters are valid");
+        }
     }
+    table
+}
 
-    points
+/// Score a guide straight from its bytes, looking each round up in the [`score_table`]
+///
+/// Rounds are read four bytes at a time where the buffer allows, falling back to a byte at a
+/// time across buffer boundaries. A missing final newline and `\r\n` line endings are accepted.
+pub fn score_bytes<R: BufRead>(mut input: R, strategy: Strategy) -> Result<usize, String> {
+    let table = score_table(strategy);
+    let mut points = 0;
+    // Progress through the current round: 0 before their letter, 1 before the space, 2 before
+    // the second letter, 3 before the line ending, and 4 between a `\r` and its `\n`, which may
+    // be in the next buffer
+    let mut state = 0;
+    let mut theirs = 0;
+    let mut offset = 0;
+    loop {
+        let buf = input
+            .fill_buf()
+            .map_err(|e| format!("failed to read guide: {e}"))?;
+        if buf.is_empty() {
+            break;
+        }
+        let len = buf.len();
+        let mut i = 0;
+        while i < len {
+            if state == 0 && i + 4 <= len {
+                if let [t @ b'A'..=b'C', b' ', m @ b'X'..=b'Z', b'\n'] = buf[i..i + 4] {
+                    points += table[usize::from(t - b'A')][usize::from(m - b'X')];
+                    i += 4;
+                    continue;
+                }
+            }
+            let byte = buf[i];
+            match (state, byte) {
+                (0, b'A'..=b'C') => theirs = usize::from(byte - b'A'),
+                (1, b' ') => {}
+                (2, b'X'..=b'Z') => points += table[theirs][usize::from(byte - b'X')],
+                (3, b'\r') => {}
+                (3 | 4, b'\n') => {
+                    state = 0;
+                    i += 1;
+                    continue;
+                }
+                _ => {
+                    return Err(format!(
+                        "unexpected byte {:?} at offset {}",
+                        char::from(byte),
+                        offset + i
+                    ))
+                }
+            }
+            state += 1;
+            i += 1;
+        }
+        offset += len;
+        input.consume(len);
+    }
+    match state {
+        0 | 3 => Ok(points),
+        _ => Err("guide ends part way through a round".to_string()),
+    }
 }
 
-#[derive(PartialEq, Eq)]
-enum Play {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Play {
     Rock,
     Paper,
     Scissors,
@@ -62,40 +150,24 @@ impl PartialOrd for Play {
 }
 
 impl Play {
-    fn score(&self, theirs: &Play) -> usize {
-        if self > theirs {
-            6
-        } else if self < theirs {
-            0
-        } else {
-            3
-        }
+    pub const ALL: [Play; 3] = [Play::Rock, Play::Paper, Play::Scissors];
+
+    fn index(&self) -> usize {
+        Weapon::from(*self).0
     }
 
-    fn play_points(&self) -> usize {
-        match self {
-            Play::Rock => 1,
-            Play::Paper => 2,
-            Play::Scissors => 3,
-        }
+    /// Points for playing `self` against `theirs`, as scored in both parts
+    fn points(&self, theirs: &Play) -> usize {
+        classic().score((*self).into(), (*theirs).into())
     }
 
     fn needed_for(desired: &Outcome, theirs: &Play) -> Self {
-        match (desired, theirs) {
-            (Outcome::Win, Play::Rock) => Self::Paper,
-            (Outcome::Win, Play::Paper) => Self::Scissors,
-            (Outcome::Win, Play::Scissors) => Self::Rock,
-            (Outcome::Draw, Play::Rock) => Self::Rock,
-            (Outcome::Draw, Play::Paper) => Self::Paper,
-            (Outcome::Draw, Play::Scissors) => Self::Scissors,
-            (Outcome::Loss, Play::Rock) => Self::Scissors,
-            (Outcome::Loss, Play::Paper) => Self::Rock,
-            (Outcome::Loss, Play::Scissors) => Self::Paper,
-        }
+        Self::ALL[classic().needed_for(*desired, (*theirs).into()).0]
     }
 }
 
-enum Outcome {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
     Win,
     Draw,
     Loss,
@@ -113,3 +185,1117 @@ impl TryFrom<char> for Outcome {
         }
     }
 }
+
+#[test]
+fn test_play_ordering() {
+    let plays = [Play::Rock, Play::Paper, Play::Scissors];
+    for a in plays {
+        for b in plays {
+            let wins = [a > b, b > a].into_iter().filter(|w| *w).count();
+            assert_eq!(wins, usize::from(a != b), "{a:?} vs {b:?}");
+            assert_eq!(classic().beats(a.into(), b.into()), a > b);
+        }
+    }
+    // Each play beats the one before it, and loses to the one after it, around the cycle:
+    for (i, play) in plays.iter().enumerate() {
+        assert!(play > &plays[(i + 2) % 3]);
+        assert!(play < &plays[(i + 1) % 3]);
+    }
+}
+
+#[test]
+fn test_needed_for_achieves_outcome() {
+    for theirs in [Play::Rock, Play::Paper, Play::Scissors] {
+        for (desired, score) in [(Outcome::Loss, 0), (Outcome::Draw, 3), (Outcome::Win, 6)] {
+            let mine = Play::needed_for(&desired, &theirs);
+            assert_eq!(mine.points(&theirs) - (mine.index() + 1), score);
+        }
+    }
+}
+
+/// A weapon in a [`Game`], identified by its position in the game's cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Weapon(pub usize);
+
+impl From<Play> for Weapon {
+    fn from(play: Play) -> Self {
+        match play {
+            Play::Rock => Weapon(0),
+            Play::Paper => Weapon(1),
+            Play::Scissors => Weapon(2),
+        }
+    }
+}
+
+/// How the second column of a strategy guide is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// The weapon to play, as in part 1
+    Weapon,
+    /// The outcome to aim for, as in part 2
+    Outcome,
+}
+
+/// A generalised rock-paper-scissors game over an odd number of weapons in a balanced cycle
+///
+/// Every weapon beats the `(n - 1) / 2` weapons that come before it in the cycle, and loses to
+/// the `(n - 1) / 2` that come after it, so each pair of distinct weapons has exactly one winner.
+///
+/// In a strategy guide, the opponent's weapons are written `A`, `B`, `C`, and so on, while our
+/// own are written with the last `n` letters of the alphabet, ending at `Z`.
+#[derive(Debug, Clone)]
+pub struct Game {
+    names: Vec<String>,
+    shape_points: Vec<usize>,
+    win_points: usize,
+    draw_points: usize,
+    loss_points: usize,
+}
+
+impl Game {
+    /// Create a game from its weapons in cycle order, scoring 1 point for playing the first
+    /// weapon, 2 for the second, and so on, plus 6 for a win and 3 for a draw
+    pub fn new<S: ToString>(names: &[S]) -> Result<Self, String> {
+        let n = names.len();
+        if n < 3 || n.is_multiple_of(2) {
+            return Err(format!(
+                "a balanced game needs an odd number of weapons, got {n}"
+            ));
+        }
+        if n > 26 {
+            return Err(format!(
+                "at most 26 weapons can be written in a guide, got {n}"
+            ));
+        }
+        Ok(Self {
+            names: names.iter().map(ToString::to_string).collect(),
+            shape_points: (1..=n).collect(),
+            win_points: 6,
+            draw_points: 3,
+            loss_points: 0,
+        })
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Self::new(&["Rock", "Paper", "Scissors"]).expect("valid game")
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Self::new(&["Rock", "Spock", "Paper", "Lizard", "Scissors"]).expect("valid game")
+    }
+
+    pub fn rps_7() -> Self {
+        Self::new(&[
+            "Water", "Air", "Paper", "Sponge", "Scissors", "Fire", "Rock",
+        ])
+        .expect("valid game")
+    }
+
+    pub fn rps_15() -> Self {
+        Self::new(&[
+            "Gun",
+            "Lightning",
+            "Devil",
+            "Dragon",
+            "Water",
+            "Air",
+            "Paper",
+            "Sponge",
+            "Wolf",
+            "Tree",
+            "Human",
+            "Snake",
+            "Scissors",
+            "Fire",
+            "Rock",
+        ])
+        .expect("valid game")
+    }
+
+    /// Look up one of the built-in games by name
+    pub fn by_name(name: &str) -> Result<Self, String> {
+        match name {
+            "rps" => Ok(Self::rock_paper_scissors()),
+            "rpsls" => Ok(Self::rock_paper_scissors_lizard_spock()),
+            "rps7" => Ok(Self::rps_7()),
+            "rps15" => Ok(Self::rps_15()),
+            _ => Err(format!(
+                "unknown game {name:?}, expected rps, rpsls, rps7 or rps15"
+            )),
+        }
+    }
+
+    /// Set the points scored for playing each weapon, in cycle order
+    pub fn with_shape_points(mut self, points: Vec<usize>) -> Result<Self, String> {
+        if points.len() != self.n_weapons() {
+            return Err(format!(
+                "expected shape points for {} weapons, got {}",
+                self.n_weapons(),
+                points.len()
+            ));
+        }
+        self.shape_points = points;
+        Ok(self)
+    }
+
+    /// Set the points scored for a win, draw and loss
+    pub fn with_outcome_points(mut self, win: usize, draw: usize, loss: usize) -> Self {
+        self.win_points = win;
+        self.draw_points = draw;
+        self.loss_points = loss;
+        self
+    }
+
+    pub fn n_weapons(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn weapons(&self) -> impl Iterator<Item = Weapon> {
+        (0..self.n_weapons()).map(Weapon)
+    }
+
+    pub fn name(&self, weapon: Weapon) -> &str {
+        &self.names[weapon.0]
+    }
+
+    /// Find a weapon by its name, ignoring case
+    pub fn weapon(&self, name: &str) -> Result<Weapon, String> {
+        self.weapons()
+            .find(|w| self.name(*w).eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("no weapon named {name:?}"))
+    }
+
+    pub fn beats(&self, mine: Weapon, theirs: Weapon) -> bool {
+        let n = self.n_weapons();
+        let distance = (mine.0 + n - theirs.0) % n;
+        (1..=n / 2).contains(&distance)
+    }
+
+    pub fn outcome(&self, mine: Weapon, theirs: Weapon) -> Outcome {
+        if mine == theirs {
+            Outcome::Draw
+        } else if self.beats(mine, theirs) {
+            Outcome::Win
+        } else {
+            Outcome::Loss
+        }
+    }
+
+    pub fn outcome_points(&self, outcome: Outcome) -> usize {
+        match outcome {
+            Outcome::Win => self.win_points,
+            Outcome::Draw => self.draw_points,
+            Outcome::Loss => self.loss_points,
+        }
+    }
+
+    pub fn shape_points(&self, weapon: Weapon) -> usize {
+        self.shape_points[weapon.0]
+    }
+
+    /// The points we score for a round
+    pub fn score(&self, mine: Weapon, theirs: Weapon) -> usize {
+        self.outcome_points(self.outcome(mine, theirs)) + self.shape_points(mine)
+    }
+
+    /// The weapon to play against `theirs` for the `desired` outcome
+    ///
+    /// When several weapons give that outcome, the one worth the most points is chosen.
+    pub fn needed_for(&self, desired: Outcome, theirs: Weapon) -> Weapon {
+        self.weapons()
+            .filter(|mine| self.outcome(*mine, theirs) == desired)
+            .max_by_key(|mine| (self.shape_points(*mine), Reverse(mine.0)))
+            .expect("every outcome is possible in a balanced game")
+    }
+
+    pub fn their_weapon(&self, c: char) -> Result<Weapon, String> {
+        let i = (c as usize).wrapping_sub('A' as usize);
+        (i < self.n_weapons())
+            .then_some(Weapon(i))
+            .ok_or_else(|| format!("invalid char for opponent's weapon: {c}"))
+    }
+
+    pub fn my_weapon(&self, c: char) -> Result<Weapon, String> {
+        let first = 'Z' as usize + 1 - self.n_weapons();
+        let i = (c as usize).wrapping_sub(first);
+        (i < self.n_weapons())
+            .then_some(Weapon(i))
+            .ok_or_else(|| format!("invalid char for own weapon: {c}"))
+    }
+
+    /// Score a round of the strategy guide, reading the second column with the `strategy`
+    pub fn score_round(&self, line: &str, strategy: Strategy) -> Result<usize, String> {
+        let mut chars = line.chars();
+        let (theirs, second) = match (chars.next(), chars.next(), chars.next(), chars.next()) {
+            (Some(theirs), Some(' '), Some(second), None) => (self.their_weapon(theirs)?, second),
+            _ => return Err(format!("invalid round: {line:?}")),
+        };
+        let mine = match strategy {
+            Strategy::Weapon => self.my_weapon(second)?,
+            Strategy::Outcome => self.needed_for(Outcome::try_from(second)?, theirs),
+        };
+        Ok(self.score(mine, theirs))
+    }
+}
+
+/// Total the score of a strategy guide played under the rules of the `game`
+pub fn score_guide<R: BufRead>(input: R, game: &Game, strategy: Strategy) -> Result<usize, String> {
+    let mut points = 0;
+    for line in input.lines() {
+        let line = line.map_err(|e| format!("failed to read line: {e}"))?;
+        points += game.score_round(&line, strategy)?;
+    }
+    Ok(points)
+}
+
+impl std::str::FromStr for Outcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "win" => Ok(Self::Win),
+            "draw" => Ok(Self::Draw),
+            "loss" | "lose" => Ok(Self::Loss),
+            _ => Err(format!("invalid outcome {s:?}")),
+        }
+    }
+}
+
+/// The most weapons for which [`Interpretation::all`] tries every weapon assignment
+pub const MAX_PERMUTED_WEAPONS: usize = 5;
+
+/// What the letters in the second column of the guide stand for
+#[derive(Debug, Clone)]
+pub enum SecondColumn {
+    Weapon(HashMap<char, Weapon>),
+    Outcome(HashMap<char, Outcome>),
+}
+
+/// One way of reading a strategy guide: the letters used in each column, and the rules of the
+/// game used to score it
+#[derive(Debug, Clone)]
+pub struct Interpretation {
+    pub name: String,
+    game: Game,
+    opponent: HashMap<char, Weapon>,
+    second: SecondColumn,
+}
+
+impl Interpretation {
+    /// Read the guide with the `game`'s own letters, as described on [`Game`]
+    pub fn new(name: &str, game: Game, strategy: Strategy) -> Self {
+        let letters = |first: char| {
+            game.weapons()
+                .map(|w| (char::from(first as u8 + w.0 as u8), w))
+                .collect::<HashMap<char, Weapon>>()
+        };
+        let opponent = letters('A');
+        let second = match strategy {
+            Strategy::Weapon => {
+                SecondColumn::Weapon(letters(char::from(b'Z' + 1 - game.n_weapons() as u8)))
+            }
+            Strategy::Outcome => SecondColumn::Outcome(HashMap::from([
+                ('X', Outcome::Loss),
+                ('Y', Outcome::Draw),
+                ('Z', Outcome::Win),
+            ])),
+        };
+        Self {
+            name: name.to_string(),
+            game,
+            opponent,
+            second,
+        }
+    }
+
+    /// Every way of assigning the opponent's letters to distinct weapons, together with every
+    /// way of assigning the letters in the second column to distinct weapons, or to distinct
+    /// outcomes, under the `game`'s rules
+    ///
+    /// Games with more than [`MAX_PERMUTED_WEAPONS`] weapons have too many weapon assignments
+    /// to try, so only the `game`'s own letters are used for their weapons.
+    pub fn all(game: &Game) -> Vec<Self> {
+        let n = game.n_weapons();
+        let orders = || -> Box<dyn Iterator<Item = Vec<usize>>> {
+            if n <= MAX_PERMUTED_WEAPONS {
+                Box::new(permutations(n))
+            } else {
+                Box::new(std::iter::once((0..n).collect()))
+            }
+        };
+        let letters = |first: u8, order: Vec<usize>| {
+            (0..n)
+                .zip(order)
+                .map(|(i, w)| (char::from(first + i as u8), Weapon(w)))
+                .collect::<HashMap<char, Weapon>>()
+        };
+        let outcomes = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+        let mut all = Vec::new();
+        for opponent in orders() {
+            let opponent = letters(b'A', opponent);
+            let as_weapons =
+                orders().map(|order| SecondColumn::Weapon(letters(b'Z' + 1 - n as u8, order)));
+            let as_outcomes = permutations(outcomes.len()).map(|order| {
+                SecondColumn::Outcome(
+                    ['X', 'Y', 'Z']
+                        .into_iter()
+                        .zip(order)
+                        .map(|(c, o)| (c, outcomes[o]))
+                        .collect(),
+                )
+            });
+            for second in as_weapons.chain(as_outcomes) {
+                let mut interpretation = Self::new("", game.clone(), Strategy::Weapon);
+                interpretation.opponent = opponent.clone();
+                interpretation.second = second;
+                interpretation.name = interpretation.describe();
+                all.push(interpretation);
+            }
+        }
+        all
+    }
+
+    /// Describe both columns, e.g., `A=Rock B=Paper C=Scissors X=Loss Y=Draw Z=Win`
+    fn describe(&self) -> String {
+        let mut pairs = self
+            .opponent
+            .iter()
+            .map(|(c, w)| format!("{c}={}", self.game.name(*w)))
+            .collect::<Vec<String>>();
+        match &self.second {
+            SecondColumn::Weapon(map) => pairs.extend(
+                map.iter()
+                    .map(|(c, w)| format!("{c}={}", self.game.name(*w))),
+            ),
+            SecondColumn::Outcome(map) => {
+                pairs.extend(map.iter().map(|(c, o)| format!("{c}={o:?}")))
+            }
+        }
+        pairs.sort();
+        pairs.join(" ")
+    }
+
+    pub fn score_round(&self, line: &str) -> Result<usize, String> {
+        let mut chars = line.chars();
+        let (first, second) = match (chars.next(), chars.next(), chars.next(), chars.next()) {
+            (Some(first), Some(' '), Some(second), None) => (first, second),
+            _ => return Err(format!("invalid round: {line:?}")),
+        };
+        let theirs = *self
+            .opponent
+            .get(&first)
+            .ok_or_else(|| format!("invalid char for opponent's weapon: {first}"))?;
+        let mine = match &self.second {
+            SecondColumn::Weapon(map) => *map
+                .get(&second)
+                .ok_or_else(|| format!("invalid char for own weapon: {second}"))?,
+            SecondColumn::Outcome(map) => self.game.needed_for(
+                *map.get(&second)
+                    .ok_or_else(|| format!("invalid char for outcome: {second}"))?,
+                theirs,
+            ),
+        };
+        Ok(self.game.score(mine, theirs))
+    }
+
+    pub fn score_guide<R: BufRead>(&self, input: R) -> Result<usize, String> {
+        let mut points = 0;
+        for line in input.lines() {
+            let line = line.map_err(|e| format!("failed to read line: {e}"))?;
+            points += self.score_round(&line)?;
+        }
+        Ok(points)
+    }
+}
+
+/// Parse a list of interpretations of the strategy guide
+///
+/// Each interpretation is a section headed by its name in square brackets, followed by
+/// `key = value` lines, any of which may be left out to keep the rules of the puzzle:
+///
+/// ```text
+/// # Lines starting with a hash are comments
+/// [x-means-paper]
+/// game = rps                       # rps, rpsls, rps7 or rps15
+/// strategy = weapon                # weapon or outcome
+/// opponent = A:Rock B:Paper C:Scissors
+/// second = X:Paper Y:Scissors Z:Rock
+/// shape = Rock:1 Paper:2 Scissors:3
+/// win = 6
+/// draw = 3
+/// loss = 0
+/// ```
+///
+/// For the `outcome` strategy, the second column maps letters to `Win`, `Draw` or `Loss`.
+pub fn parse_interpretations(config: &str) -> Result<Vec<Interpretation>, String> {
+    let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for (n, line) in config.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name.trim().to_string(), Vec::new()));
+        } else if let Some((key, value)) = line.split_once('=') {
+            let (_, entries) = sections
+                .last_mut()
+                .ok_or_else(|| format!("line {}: setting outside of a section", n + 1))?;
+            entries.push((key.trim().to_string(), value.trim().to_string()));
+        } else {
+            return Err(format!("line {}: expected [name] or key = value", n + 1));
+        }
+    }
+    sections
+        .into_iter()
+        .map(|(name, entries)| {
+            interpretation_from(&name, &entries).map_err(|e| format!("[{name}]: {e}"))
+        })
+        .collect()
+}
+
+fn interpretation_from(name: &str, entries: &[(String, String)]) -> Result<Interpretation, String> {
+    let get = |key: &str| {
+        entries
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    };
+    let number = |key: &str, default: usize| {
+        get(key).map_or(Ok(default), |v| {
+            v.parse::<usize>()
+                .map_err(|e| format!("invalid {key} points {v:?}: {e}"))
+        })
+    };
+    if let Some((key, _)) = entries.iter().find(|(k, _)| {
+        ![
+            "game", "strategy", "opponent", "second", "shape", "win", "draw", "loss",
+        ]
+        .contains(&k.as_str())
+    }) {
+        return Err(format!("unknown setting {key:?}"));
+    }
+    let mut game = Game::by_name(get("game").unwrap_or("rps"))?.with_outcome_points(
+        number("win", 6)?,
+        number("draw", 3)?,
+        number("loss", 0)?,
+    );
+    if let Some(shape) = get("shape") {
+        let mut points = game.shape_points.clone();
+        for (weapon, value) in pairs(shape)? {
+            points[game.weapon(weapon)?.0] = value
+                .parse()
+                .map_err(|e| format!("invalid shape points {value:?}: {e}"))?;
+        }
+        game = game.with_shape_points(points)?;
+    }
+    let strategy = match get("strategy").unwrap_or("weapon") {
+        "weapon" => Strategy::Weapon,
+        "outcome" => Strategy::Outcome,
+        s => {
+            return Err(format!(
+                "unknown strategy {s:?}, expected weapon or outcome"
+            ))
+        }
+    };
+    let mut interpretation = Interpretation::new(name, game, strategy);
+    if let Some(opponent) = get("opponent") {
+        interpretation.opponent = pairs(opponent)?
+            .into_iter()
+            .map(|(c, w)| Ok((letter(c)?, interpretation.game.weapon(w)?)))
+            .collect::<Result<_, String>>()?;
+    }
+    if let Some(second) = get("second") {
+        interpretation.second = match strategy {
+            Strategy::Weapon => SecondColumn::Weapon(
+                pairs(second)?
+                    .into_iter()
+                    .map(|(c, w)| Ok((letter(c)?, interpretation.game.weapon(w)?)))
+                    .collect::<Result<_, String>>()?,
+            ),
+            Strategy::Outcome => SecondColumn::Outcome(
+                pairs(second)?
+                    .into_iter()
+                    .map(|(c, o)| Ok((letter(c)?, o.parse()?)))
+                    .collect::<Result<_, String>>()?,
+            ),
+        };
+    }
+    Ok(interpretation)
+}
+
+/// Split a list like `A:Rock B:Paper` into its pairs
+fn pairs(list: &str) -> Result<Vec<(&str, &str)>, String> {
+    list.split_whitespace()
+        .map(|pair| {
+            pair.split_once(':')
+                .ok_or_else(|| format!("expected a key:value pair, found {pair:?}"))
+        })
+        .collect()
+}
+
+fn letter(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(format!("expected a single letter, found {s:?}")),
+    }
+}
+
+/// Every ordering of `0..n`, generated lazily by Heap's algorithm
+struct Permutations {
+    order: Vec<usize>,
+    counters: Vec<usize>,
+    i: usize,
+    first: bool,
+}
+
+fn permutations(n: usize) -> Permutations {
+    Permutations {
+        order: (0..n).collect(),
+        counters: vec![0; n],
+        i: 1,
+        first: true,
+    }
+}
+
+impl Iterator for Permutations {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        if std::mem::take(&mut self.first) {
+            return Some(self.order.clone());
+        }
+        while self.i < self.order.len() {
+            if self.counters[self.i] < self.i {
+                let j = if self.i.is_multiple_of(2) {
+                    0
+                } else {
+                    self.counters[self.i]
+                };
+                self.order.swap(j, self.i);
+                self.counters[self.i] += 1;
+                self.i = 1;
+                return Some(self.order.clone());
+            }
+            self.counters[self.i] = 0;
+            self.i += 1;
+        }
+        None
+    }
+}
+
+/// What can be learned about the opponent from the first column of a strategy guide
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    rounds: usize,
+    /// How often the opponent played each of [`Play::ALL`]
+    frequencies: [usize; 3],
+    /// How often the opponent followed one play with another, indexed by `[previous][next]`
+    transitions: [[usize; 3]; 3],
+    first: Option<Play>,
+    part_1: usize,
+    part_2: usize,
+}
+
+impl Analysis {
+    pub fn from_reader<R: BufRead>(input: R) -> Result<Self, String> {
+        let mut analysis = Self {
+            rounds: 0,
+            frequencies: [0; 3],
+            transitions: [[0; 3]; 3],
+            first: None,
+            part_1: 0,
+            part_2: 0,
+        };
+        let mut previous: Option<Play> = None;
+        for line in input.lines() {
+            let line = line.map_err(|e| format!("failed to read line: {e}"))?;
+            let (theirs, mine) = parse_round::<Play>(&line)?;
+            let (_, desired) = parse_round::<Outcome>(&line)?;
+            analysis.rounds += 1;
+            analysis.frequencies[theirs.index()] += 1;
+            match previous {
+                Some(previous) => analysis.transitions[previous.index()][theirs.index()] += 1,
+                None => analysis.first = Some(theirs),
+            }
+            previous = Some(theirs);
+            analysis.part_1 += mine.points(&theirs);
+            analysis.part_2 += Play::needed_for(&desired, &theirs).points(&theirs);
+        }
+        Ok(analysis)
+    }
+
+    /// The play scoring the most points against an opponent who plays as often as in `counts`,
+    /// along with the points it scores in total
+    fn best_response(counts: &[usize; 3]) -> (Play, usize) {
+        Play::ALL
+            .into_iter()
+            .map(|mine| {
+                let points = Play::ALL
+                    .iter()
+                    .map(|theirs| counts[theirs.index()] * mine.points(theirs))
+                    .sum::<usize>();
+                (mine, points)
+            })
+            .max_by_key(|(mine, points)| (*points, Reverse(mine.index())))
+            .expect("there are plays to choose from")
+    }
+
+    /// The single play that scores the most when played every round, and its total score
+    pub fn fixed_response(&self) -> (Play, usize) {
+        Self::best_response(&self.frequencies)
+    }
+
+    /// The best response to each of the opponent's previous plays, and the total score of
+    /// playing them, opening with the fixed response
+    pub fn markov_response(&self) -> ([Play; 3], usize) {
+        let mut responses = [Play::Rock; 3];
+        let mut total = match self.first {
+            Some(first) => self.fixed_response().0.points(&first),
+            None => 0,
+        };
+        for (previous, counts) in self.transitions.iter().enumerate() {
+            let (response, points) = Self::best_response(counts);
+            responses[previous] = response;
+            total += points;
+        }
+        (responses, total)
+    }
+
+    fn per_round(&self, total: usize) -> f64 {
+        total as f64 / self.rounds.max(1) as f64
+    }
+}
+
+impl std::fmt::Display for Analysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let percent = |count: usize, total: usize| 100.0 * count as f64 / total.max(1) as f64;
+        writeln!(f, "rounds: {}", self.rounds)?;
+        writeln!(f, "opponent frequencies:")?;
+        for play in Play::ALL {
+            let count = self.frequencies[play.index()];
+            let share = percent(count, self.rounds);
+            writeln!(f, "  {:<8} {count:>6} ({share:.1}%)", format!("{play:?}"))?;
+        }
+        writeln!(f, "opponent transitions (previous -> next):")?;
+        writeln!(
+            f,
+            "  {:<8} {:>8} {:>8} {:>8}",
+            "", "Rock", "Paper", "Scissors"
+        )?;
+        for play in Play::ALL {
+            let row = &self.transitions[play.index()];
+            let total = row.iter().sum::<usize>();
+            write!(f, "  {:<8}", format!("{play:?}"))?;
+            for count in row {
+                write!(f, " {:>7.1}%", percent(*count, total))?;
+            }
+            writeln!(f)?;
+        }
+        let (fixed, fixed_total) = self.fixed_response();
+        writeln!(
+            f,
+            "best fixed response: always {fixed:?}, {:.3} per round ({fixed_total} total)",
+            self.per_round(fixed_total)
+        )?;
+        let (responses, markov_total) = self.markov_response();
+        let responses = Play::ALL
+            .iter()
+            .map(|play| format!("{:?} after {play:?}", responses[play.index()]))
+            .collect::<Vec<String>>()
+            .join(", ");
+        writeln!(
+            f,
+            "best Markov response: {responses}, {:.3} per round ({markov_total} total)",
+            self.per_round(markov_total)
+        )?;
+        for (label, total) in [("part 1", self.part_1), ("part 2", self.part_2)] {
+            writeln!(
+                f,
+                "guide read as in {label}: {:.3} per round ({total} total)",
+                self.per_round(total)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A tournament entrant, playing the second column of its strategy guide as in part 1
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub name: String,
+    plays: Vec<Play>,
+}
+
+impl Player {
+    pub fn from_reader<R: BufRead>(name: &str, input: R) -> Result<Self, String> {
+        let plays = input
+            .lines()
+            .map(|line| {
+                let line = line.map_err(|e| format!("failed to read line: {e}"))?;
+                parse_round::<Play>(&line).map(|(_, mine)| mine)
+            })
+            .collect::<Result<Vec<Play>, String>>()?;
+        if plays.is_empty() {
+            return Err(format!("guide for {name} has no rounds"));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            plays,
+        })
+    }
+
+    /// Play a match against `other`, returning the points scored by each player
+    ///
+    /// A match lasts as long as the longer of the two guides, with the shorter one starting over
+    /// from the top when it runs out.
+    pub fn against(&self, other: &Player) -> (usize, usize) {
+        let rounds = self.plays.len().max(other.plays.len());
+        let mine = self.plays.iter().cycle();
+        let theirs = other.plays.iter().cycle();
+        mine.zip(theirs)
+            .take(rounds)
+            .fold((0, 0), |(a, b), (mine, theirs)| {
+                (a + mine.points(theirs), b + theirs.points(mine))
+            })
+    }
+}
+
+/// The results of every player meeting every other player once
+#[derive(Debug, Clone)]
+pub struct RoundRobin {
+    names: Vec<String>,
+    /// Points scored by the row player against the column player
+    head_to_head: Vec<Vec<usize>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Standing {
+    pub player: usize,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub scored: usize,
+}
+
+impl Standing {
+    /// Three points for a match win and one for a draw
+    pub fn points(&self) -> usize {
+        3 * self.wins + self.draws
+    }
+}
+
+pub fn round_robin(players: &[Player]) -> RoundRobin {
+    let mut head_to_head = vec![vec![0; players.len()]; players.len()];
+    for (i, a) in players.iter().enumerate() {
+        for (j, b) in players.iter().enumerate().skip(i + 1) {
+            let (a_points, b_points) = a.against(b);
+            head_to_head[i][j] = a_points;
+            head_to_head[j][i] = b_points;
+        }
+    }
+    RoundRobin {
+        names: players.iter().map(|p| p.name.clone()).collect(),
+        head_to_head,
+    }
+}
+
+impl RoundRobin {
+    /// The standings, best first, ranked by match points then by points scored
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings = (0..self.names.len())
+            .map(|i| {
+                let mut standing = Standing {
+                    player: i,
+                    ..Default::default()
+                };
+                for j in (0..self.names.len()).filter(|j| *j != i) {
+                    let (mine, theirs) = (self.head_to_head[i][j], self.head_to_head[j][i]);
+                    match mine.cmp(&theirs) {
+                        std::cmp::Ordering::Greater => standing.wins += 1,
+                        std::cmp::Ordering::Equal => standing.draws += 1,
+                        std::cmp::Ordering::Less => standing.losses += 1,
+                    }
+                    standing.scored += mine;
+                }
+                standing
+            })
+            .collect::<Vec<Standing>>();
+        standings.sort_by_key(|s| (Reverse(s.points()), Reverse(s.scored), s.player));
+        standings
+    }
+}
+
+impl std::fmt::Display for RoundRobin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.names.iter().map(|n| n.len()).max().unwrap_or(0).max(6);
+        writeln!(
+            f,
+            "{:>4}  {:<width$} {:>3} {:>3} {:>3} {:>6} {:>8}",
+            "rank", "player", "W", "D", "L", "points", "scored"
+        )?;
+        for (rank, s) in self.standings().iter().enumerate() {
+            writeln!(
+                f,
+                "{:>4}  {:<width$} {:>3} {:>3} {:>3} {:>6} {:>8}",
+                rank + 1,
+                self.names[s.player],
+                s.wins,
+                s.draws,
+                s.losses,
+                s.points(),
+                s.scored
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "head to head (points scored by row against column):")?;
+        write!(f, "{:<width$}", "")?;
+        for name in &self.names {
+            write!(f, " {name:>width$}")?;
+        }
+        writeln!(f)?;
+        for (i, row) in self.head_to_head.iter().enumerate() {
+            write!(f, "{:<width$}", self.names[i])?;
+            for (j, points) in row.iter().enumerate() {
+                if i == j {
+                    write!(f, " {:>width$}", "-")?;
+                } else {
+                    write!(f, " {points:>width$}")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The two players in a match and their points; a player without an opponent gets a bye to the
+/// next round
+type Match = (usize, Option<usize>, (usize, usize));
+
+/// A single-elimination bracket, seeded in the order the players are given
+#[derive(Debug, Clone)]
+pub struct Bracket {
+    names: Vec<String>,
+    rounds: Vec<Vec<Match>>,
+    pub winner: usize,
+}
+
+/// Play a single-elimination bracket, where a drawn match goes to the higher seed
+pub fn elimination(players: &[Player]) -> Result<Bracket, String> {
+    if players.is_empty() {
+        return Err("a tournament needs at least one player".to_string());
+    }
+    let mut remaining = (0..players.len()).collect::<Vec<usize>>();
+    let mut rounds = Vec::new();
+    while remaining.len() > 1 {
+        let mut matches = Vec::new();
+        let mut next = Vec::new();
+        for pair in remaining.chunks(2) {
+            match *pair {
+                [a, b] => {
+                    let (a_points, b_points) = players[a].against(&players[b]);
+                    next.push(if b_points > a_points { b } else { a });
+                    matches.push((a, Some(b), (a_points, b_points)));
+                }
+                [a] => {
+                    next.push(a);
+                    matches.push((a, None, (0, 0)));
+                }
+                _ => unreachable!("chunks have one or two players"),
+            }
+        }
+        rounds.push(matches);
+        remaining = next;
+    }
+    Ok(Bracket {
+        names: players.iter().map(|p| p.name.clone()).collect(),
+        rounds,
+        winner: remaining[0],
+    })
+}
+
+impl std::fmt::Display for Bracket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (n, matches) in self.rounds.iter().enumerate() {
+            writeln!(f, "round {}:", n + 1)?;
+            for (a, b, (a_points, b_points)) in matches {
+                match b {
+                    Some(b) => writeln!(
+                        f,
+                        "  {} {a_points} - {b_points} {}",
+                        self.names[*a], self.names[*b]
+                    )?,
+                    None => writeln!(f, "  {} (bye)", self.names[*a])?,
+                }
+            }
+        }
+        writeln!(f, "winner: {}", self.names[self.winner])
+    }
+}
+
+#[test]
+fn test_games_are_balanced() {
+    for game in ["rps", "rpsls", "rps7", "rps15"].map(|g| Game::by_name(g).unwrap()) {
+        for a in game.weapons() {
+            let wins = game.weapons().filter(|b| game.beats(a, *b)).count();
+            assert_eq!(wins, game.n_weapons() / 2, "{}", game.name(a));
+            for b in game.weapons() {
+                assert!(!(game.beats(a, b) && game.beats(b, a)));
+            }
+        }
+    }
+    let rpsls = Game::rock_paper_scissors_lizard_spock();
+    let weapon = |name| rpsls.weapons().find(|w| rpsls.name(*w) == name).unwrap();
+    assert!(rpsls.beats(weapon("Lizard"), weapon("Spock")));
+    assert!(rpsls.beats(weapon("Spock"), weapon("Scissors")));
+    assert!(rpsls.beats(weapon("Paper"), weapon("Spock")));
+    assert!(rpsls.beats(weapon("Rock"), weapon("Lizard")));
+}
+
+#[test]
+fn test_classic_game_scores() {
+    // Shape points plus 6 for a win, 3 for a draw and 0 for a loss, as in the puzzle:
+    assert_eq!(
+        score_table(Strategy::Weapon),
+        [[4, 8, 3], [1, 5, 9], [7, 2, 6]]
+    );
+    assert_eq!(
+        score_table(Strategy::Outcome),
+        [[3, 4, 8], [1, 5, 9], [2, 6, 7]]
+    );
+    assert_eq!(part_1("A Y\nB X\nC Z\n".as_bytes()), 15);
+    assert_eq!(part_2("A Y\nB X\nC Z\n".as_bytes()), 12);
+}
+
+#[test]
+fn test_interpretations() {
+    let config = "
+        # Part 1 and part 2, spelled out
+        [part-1]
+        opponent = A:Rock B:Paper C:Scissors
+        second = X:Rock Y:Paper Z:Scissors
+
+        [part-2]
+        strategy = outcome
+        second = X:Loss Y:Draw Z:Win
+
+        [no-shape-points]
+        shape = Rock:0 Paper:0 Scissors:0
+        win = 1
+        draw = 0
+    ";
+    let interpretations = parse_interpretations(config).unwrap();
+    let guide = "A Y\nB X\nC Z\n";
+    let scores = interpretations
+        .iter()
+        .map(|i| i.score_guide(guide.as_bytes()).unwrap())
+        .collect::<Vec<usize>>();
+    assert_eq!(scores, [15, 12, 1]);
+    assert!(parse_interpretations("[bad]\nsecond = X:Rock Y:Stone").is_err());
+    assert!(parse_interpretations("strategy = weapon").is_err());
+
+    let all = Interpretation::all(&Game::rock_paper_scissors());
+    assert_eq!(all.len(), 6 * (6 + 6));
+    let by_name = |name: &str| all.iter().find(|i| i.name == name).unwrap();
+    assert_eq!(
+        by_name("A=Rock B=Paper C=Scissors X=Rock Y=Paper Z=Scissors")
+            .score_guide(guide.as_bytes()),
+        Ok(15)
+    );
+    assert_eq!(
+        by_name("A=Rock B=Paper C=Scissors X=Loss Y=Draw Z=Win").score_guide(guide.as_bytes()),
+        Ok(12)
+    );
+    // Reading the opponent's letters differently loses every round:
+    assert_eq!(
+        by_name("A=Scissors B=Paper C=Rock X=Rock Y=Paper Z=Scissors")
+            .score_guide(guide.as_bytes()),
+        Ok(6)
+    );
+
+    // Every weapon assignment is tried up to the limit, but only the game's own above it:
+    let mut orders = permutations(4).collect::<Vec<Vec<usize>>>();
+    orders.sort();
+    orders.dedup();
+    assert_eq!(orders.len(), 24);
+    assert_eq!(
+        Interpretation::all(&Game::rock_paper_scissors_lizard_spock()).len(),
+        120 * (120 + 6)
+    );
+    let all = Interpretation::all(&Game::rps_15());
+    assert_eq!(all.len(), 1 + 6);
+    assert_eq!(
+        all[0].name,
+        Interpretation::new("", Game::rps_15(), Strategy::Weapon).describe()
+    );
+}
+
+#[test]
+fn test_analysis() {
+    // An opponent cycling through the plays can be beaten every round once it is predicted:
+    let analysis = Analysis::from_reader("A X\nB X\nC X\nA X\nB X\nC X\n".as_bytes()).unwrap();
+    assert_eq!(analysis.fixed_response(), (Play::Scissors, 36));
+    let (responses, total) = analysis.markov_response();
+    assert_eq!(responses, [Play::Scissors, Play::Rock, Play::Paper]);
+    assert_eq!(total, 3 + 9 + 7 + 8 + 9 + 7);
+    assert_eq!(
+        analysis.part_1,
+        part_1("A X\nB X\nC X\nA X\nB X\nC X\n".as_bytes())
+    );
+}
+
+#[test]
+fn test_tournament() {
+    let player = |name: &str, guide: &str| Player::from_reader(name, guide.as_bytes()).unwrap();
+    let rock = player("rock", "A X\n");
+    let paper = player("paper", "A Y\nA Y\n");
+    let cycle = player("cycle", "A X\nA Y\nA Z\n");
+    assert_eq!(rock.against(&paper), (2, 16));
+    // Over three rounds, the cycling player draws, wins and loses against rock:
+    assert_eq!(cycle.against(&rock), (4 + 8 + 3, 4 + 7 + 1));
+    assert_eq!(rock.against(&rock), (4, 4));
+
+    let players = [rock, paper, cycle];
+    let standings = round_robin(&players).standings();
+    assert_eq!(
+        standings.iter().map(|s| s.player).collect::<Vec<_>>(),
+        [1, 2, 0]
+    );
+    assert_eq!(standings.iter().map(|s| s.points()).sum::<usize>(), 8);
+    assert_eq!(elimination(&players).unwrap().winner, 1);
+}
+
+#[test]
+fn test_score_bytes_matches_play() {
+    use crate::{generate, utils::Rng};
+
+    for seed in 0..20 {
+        let guide = generate::day_02(&mut Rng::new(seed), 1 + seed as usize * 37);
+        // Read in small pieces, so that rounds are split across buffer boundaries:
+        let reader = || std::io::BufReader::with_capacity(7, guide.as_bytes());
+        assert_eq!(
+            score_bytes(reader(), Strategy::Weapon),
+            Ok(part_1(guide.as_bytes()))
+        );
+        assert_eq!(
+            score_bytes(reader(), Strategy::Outcome),
+            Ok(part_2(guide.as_bytes()))
+        );
+    }
+    assert_eq!(
+        score_bytes("A Y\r\nB X\r\nC Z".as_bytes(), Strategy::Weapon),
+        Ok(15)
+    );
+    assert!(score_bytes("A Y\nD X\n".as_bytes(), Strategy::Weapon).is_err());
+    assert!(score_bytes("A Y\nB".as_bytes(), Strategy::Weapon).is_err());
+    // A carriage return only counts as part of a `\r\n` line ending, even across buffers:
+    let reader = |guide: &'static str| std::io::BufReader::with_capacity(4, guide.as_bytes());
+    assert_eq!(
+        score_bytes(reader("A Y\r\nB X\r\n"), Strategy::Weapon),
+        Ok(9)
+    );
+    assert!(score_bytes(reader("A Y\r"), Strategy::Weapon).is_err());
+    assert!(score_bytes(reader("A Y\r\r\n"), Strategy::Weapon).is_err());
+}
@@ -1,14 +1,51 @@
 use std::{
     collections::{HashMap, HashSet},
+    io::BufRead,
     ops::Div,
 };
 
-use crate::utils::get_lines;
+use crate::utils::get_buf_reader;
 
 pub fn answer_part_1() -> u32 {
+    part_1(get_buf_reader("input/day_03.txt"))
+}
+
+pub fn answer_part_2() -> u32 {
+    part_2(get_buf_reader("input/day_03.txt"))
+}
+
+/// Unlike [`Layout::misplaced`], this doesn't insist on a single misplaced item type: as in the
+/// puzzle, the first item in the second compartment that's also in the first one is taken.
+pub fn part_1<R: BufRead>(input: R) -> u32 {
     let mut sum = 0;
-    for line in get_lines("input/day_03.txt") {
+    for line in input.lines() {
+        let line = line.expect("reads rucksack");
+        let sack = parse_rucksack(&line).expect("valid rucksack");
+        let (left, right) = sack.split_at(sack.len() / 2);
+        let left = item_mask(left);
+        sum += right
+            .chars()
+            .map(get_priority)
+            .find(|priority| left & 1 << (priority - 1) != 0)
+            .expect("an item in both compartments");
+    }
+    sum
+}
+
+pub fn part_2<R: BufRead>(input: R) -> u32 {
+    Layout::default()
+        .sum_badges(input)
+        .expect("every group has a badge")
+}
+
+/// Same as [`part_1`], but looks for the shared item with a `HashSet`
+///
+/// This is kept as an oracle for testing [`part_1`].
+pub fn part_1_naive<R: BufRead>(input: R) -> u32 {
+    let mut sum = 0;
+    for line in input.lines() {
         let line = line.unwrap();
+        parse_rucksack(&line).expect("valid rucksack");
         let sack_size = line.len().div(2);
         let mut sack_1 = line;
         let sack_2 = sack_1.split_off(sack_size);
@@ -26,35 +63,670 @@ pub fn answer_part_1() -> u32 {
     sum
 }
 
-pub fn answer_part_2() -> u32 {
-    let mut sum = 0;
-    let mut buf = Vec::with_capacity(3);
-    for line in get_lines("input/day_03.txt") {
-        let line = line.unwrap();
-        if buf.len() < 3 {
-            buf.push(line);
+/// Same as [`part_2`], but finds each badge with [`process_group_naive`]
+///
+/// This is kept as an oracle for testing [`part_2`].
+pub fn part_2_naive<R: BufRead>(input: R) -> u32 {
+    let lines = input.lines().map(Result::unwrap).collect::<Vec<String>>();
+    lines
+        .chunks_exact(3)
+        .map(|group| process_group_naive(group).expect("group has a badge"))
+        .sum()
+}
+
+/// Check that a rucksack holds only item types `a` through `z` and `A` through `Z`, and can be
+/// split evenly into its two compartments
+pub fn parse_rucksack(line: &str) -> Result<&str, String> {
+    Layout::default().parse_rucksack(line)
+}
+
+/// Find the priority of the badge carried by every rucksack in the group
+pub fn process_group<S: AsRef<str>>(sacks: &[S]) -> Result<u32, String> {
+    Layout::default().badge(sacks)
+}
+
+/// How rucksacks are divided into compartments, and elves into groups
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    compartments: usize,
+    group_size: usize,
+}
+
+impl Default for Layout {
+    /// Two compartments per rucksack and groups of three elves, as in the puzzle
+    fn default() -> Self {
+        Self {
+            compartments: 2,
+            group_size: 3,
+        }
+    }
+}
+
+impl Layout {
+    pub fn new(compartments: usize, group_size: usize) -> Result<Self, String> {
+        if compartments < 2 {
+            return Err(format!(
+                "a rucksack needs at least 2 compartments, got {compartments}"
+            ));
         }
-        if buf.len() == 3 {
-            sum += process_group(buf.as_slice());
-            buf.clear();
+        if group_size == 0 {
+            return Err("a group needs at least one elf".to_string());
         }
+        Ok(Self {
+            compartments,
+            group_size,
+        })
     }
-    sum
+
+    pub fn compartments(&self) -> usize {
+        self.compartments
+    }
+
+    pub fn group_size(&self) -> usize {
+        self.group_size
+    }
+
+    /// Check that a rucksack holds only item types `a` through `z` and `A` through `Z`, and can
+    /// be split evenly into its compartments
+    pub fn parse_rucksack<'a>(&self, line: &'a str) -> Result<&'a str, String> {
+        if let Some(c) = line.chars().find(|c| !c.is_ascii_alphabetic()) {
+            return Err(format!("invalid item {c:?} in rucksack {line:?}"));
+        }
+        if !line.len().is_multiple_of(self.compartments) {
+            return Err(format!(
+                "rucksack {line:?} of {} items can't be split into {} compartments",
+                line.len(),
+                self.compartments
+            ));
+        }
+        Ok(line)
+    }
+
+    /// The rucksack's compartments, which must have been checked by [`Self::parse_rucksack`]
+    pub fn compartments_of<'a>(&self, sack: &'a str) -> impl Iterator<Item = &'a str> {
+        // Slicing rather than `chunks` gives an empty rucksack its empty compartments:
+        let size = sack.len() / self.compartments;
+        (0..self.compartments).map(move |i| &sack[i * size..(i + 1) * size])
+    }
+
+    /// Find the priority of the one item type found in every compartment of the rucksack
+    pub fn misplaced(&self, sack: &str) -> Result<u32, String> {
+        let sack = self.parse_rucksack(sack)?;
+        let common = self
+            .compartments_of(sack)
+            .fold(u64::MAX, |common, items| common & item_mask(items));
+        single_item(common).map_err(|e| format!("rucksack {sack:?} has {e} in every compartment"))
+    }
+
+    /// Find the priority of the one item type carried by every rucksack in the group
+    pub fn badge<S: AsRef<str>>(&self, sacks: &[S]) -> Result<u32, String> {
+        if sacks.len() != self.group_size {
+            return Err(format!(
+                "expected a group of {} rucksacks, got {}",
+                self.group_size,
+                sacks.len()
+            ));
+        }
+        let mut common = u64::MAX;
+        for sack in sacks {
+            common &= item_mask(self.parse_rucksack(sack.as_ref())?);
+        }
+        single_item(common).map_err(|e| format!("group has {e} in common"))
+    }
+
+    /// Sum the priorities of the misplaced item in every rucksack
+    pub fn sum_misplaced<R: BufRead>(&self, input: R) -> Result<u32, String> {
+        let mut sum = 0;
+        for (n, line) in input.lines().enumerate() {
+            let line = line.map_err(|e| format!("failed to read line: {e}"))?;
+            sum += self
+                .misplaced(&line)
+                .map_err(|e| format!("line {}: {e}", n + 1))?;
+        }
+        Ok(sum)
+    }
+
+    /// Sum the priorities of the badge of every group of consecutive rucksacks
+    pub fn sum_badges<R: BufRead>(&self, input: R) -> Result<u32, String> {
+        let mut sum = 0;
+        let mut buf = Vec::with_capacity(self.group_size);
+        for (n, line) in input.lines().enumerate() {
+            buf.push(line.map_err(|e| format!("failed to read line: {e}"))?);
+            if buf.len() == self.group_size {
+                sum += self
+                    .badge(&buf)
+                    .map_err(|e| format!("lines {}-{}: {e}", n + 2 - buf.len(), n + 1))?;
+                buf.clear();
+            }
+        }
+        if !buf.is_empty() {
+            return Err(format!(
+                "the last group has {} of {} rucksacks",
+                buf.len(),
+                self.group_size
+            ));
+        }
+        Ok(sum)
+    }
+}
+
+/// The priority of the only item type in the `mask`, or a description of what it holds instead
+fn single_item(mask: u64) -> Result<u32, String> {
+    match mask.count_ones() {
+        0 => Err("no item".to_string()),
+        1 => Ok(mask.trailing_zeros() + 1),
+        _ => {
+            let items = items_in(mask).map(String::from).collect::<Vec<String>>();
+            Err(format!("several items ({})", items.join(", ")))
+        }
+    }
+}
+
+/// The item types in the `mask`, in order of priority
+fn items_in(mask: u64) -> impl Iterator<Item = char> {
+    (0..52)
+        .filter(move |bit| mask & 1 << bit != 0)
+        .map(|bit| item(bit + 1))
+}
+
+/// The item type with the given priority, the inverse of [`get_priority`]
+fn item(priority: u32) -> char {
+    match priority {
+        1..=26 => char::from(b'a' + (priority - 1) as u8),
+        27..=52 => char::from(b'A' + (priority - 27) as u8),
+        _ => panic!("unexpected priority: {priority}"),
+    }
+}
+
+/// Something wrong with a rucksack or group that the puzzle assumes can't happen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Anomaly {
+    Invalid(String),
+    NoSharedItem,
+    SeveralSharedItems,
+    InvalidMember,
+    NoBadge,
+    SeveralBadges,
+    IncompleteGroup,
 }
 
-fn process_group(sacks: &[String]) -> u32 {
+impl std::fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Anomaly::Invalid(e) => write!(f, "{e}"),
+            Anomaly::NoSharedItem => write!(f, "no item in every compartment"),
+            Anomaly::SeveralSharedItems => write!(f, "several items in every compartment"),
+            Anomaly::InvalidMember => write!(f, "group contains an invalid rucksack"),
+            Anomaly::NoBadge => write!(f, "no item common to the group"),
+            Anomaly::SeveralBadges => write!(f, "several items common to the group"),
+            Anomaly::IncompleteGroup => write!(f, "group is missing rucksacks"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SackAudit {
+    /// The line number, counting from one
+    pub line: usize,
+    pub contents: String,
+    /// Item types found in every compartment
+    pub misplaced: Vec<char>,
+    pub anomaly: Option<Anomaly>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupAudit {
+    /// The first and last line numbers of the group
+    pub lines: (usize, usize),
+    /// Item types carried by every rucksack in the group
+    pub badges: Vec<char>,
+    pub anomaly: Option<Anomaly>,
+}
+
+/// A line-by-line account of the misplaced items and badges in every rucksack and group
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Audit {
+    pub sacks: Vec<SackAudit>,
+    pub groups: Vec<GroupAudit>,
+}
+
+impl Layout {
+    /// Audit every rucksack and group, recording anomalies instead of failing on them
+    pub fn audit<R: BufRead>(&self, input: R) -> Result<Audit, String> {
+        let mut audit = Audit::default();
+        let mut group: Option<u64> = Some(u64::MAX);
+        for (n, line) in input.lines().enumerate() {
+            let line = line.map_err(|e| format!("failed to read line: {e}"))?;
+            let sack = match self.parse_rucksack(&line) {
+                Ok(sack) => {
+                    let common = self
+                        .compartments_of(sack)
+                        .fold(u64::MAX, |common, items| common & item_mask(items));
+                    group = group.map(|mask| mask & item_mask(sack));
+                    SackAudit {
+                        line: n + 1,
+                        contents: line.clone(),
+                        misplaced: items_in(common).collect(),
+                        anomaly: match common.count_ones() {
+                            0 => Some(Anomaly::NoSharedItem),
+                            1 => None,
+                            _ => Some(Anomaly::SeveralSharedItems),
+                        },
+                    }
+                }
+                Err(e) => {
+                    group = None;
+                    SackAudit {
+                        line: n + 1,
+                        contents: line.clone(),
+                        misplaced: Vec::new(),
+                        anomaly: Some(Anomaly::Invalid(e)),
+                    }
+                }
+            };
+            audit.sacks.push(sack);
+            if audit.sacks.len().is_multiple_of(self.group_size) {
+                audit
+                    .groups
+                    .push(group_audit(n + 2 - self.group_size, n + 1, group));
+                group = Some(u64::MAX);
+            }
+        }
+        let remaining = audit.sacks.len() % self.group_size;
+        if remaining > 0 {
+            let last = audit.sacks.len();
+            let mut incomplete = group_audit(last + 1 - remaining, last, group);
+            incomplete.anomaly = Some(Anomaly::IncompleteGroup);
+            audit.groups.push(incomplete);
+        }
+        Ok(audit)
+    }
+}
+
+fn group_audit(first: usize, last: usize, mask: Option<u64>) -> GroupAudit {
+    let Some(mask) = mask else {
+        return GroupAudit {
+            lines: (first, last),
+            badges: Vec::new(),
+            anomaly: Some(Anomaly::InvalidMember),
+        };
+    };
+    GroupAudit {
+        lines: (first, last),
+        badges: items_in(mask).collect(),
+        anomaly: match mask.count_ones() {
+            0 => Some(Anomaly::NoBadge),
+            1 => None,
+            _ => Some(Anomaly::SeveralBadges),
+        },
+    }
+}
+
+impl Audit {
+    pub fn anomalies(&self) -> usize {
+        let sacks = self.sacks.iter().filter(|s| s.anomaly.is_some()).count();
+        sacks + self.groups.iter().filter(|g| g.anomaly.is_some()).count()
+    }
+
+    /// The audit as a JSON object with `rucksacks` and `groups` arrays
+    pub fn to_json(&self) -> String {
+        let items = |items: &[char]| {
+            let items = items
+                .iter()
+                .map(|c| format!("{{\"item\":\"{c}\",\"priority\":{}}}", get_priority(*c)))
+                .collect::<Vec<String>>();
+            format!("[{}]", items.join(","))
+        };
+        let anomaly = |anomaly: &Option<Anomaly>| {
+            anomaly
+                .as_ref()
+                .map_or("null".to_string(), |a| json_string(&a.to_string()))
+        };
+        let sacks = self
+            .sacks
+            .iter()
+            .map(|s| {
+                format!(
+                    "{{\"line\":{},\"contents\":{},\"misplaced\":{},\"anomaly\":{}}}",
+                    s.line,
+                    json_string(&s.contents),
+                    items(&s.misplaced),
+                    anomaly(&s.anomaly)
+                )
+            })
+            .collect::<Vec<String>>();
+        let groups = self
+            .groups
+            .iter()
+            .map(|g| {
+                format!(
+                    "{{\"lines\":[{},{}],\"badges\":{},\"anomaly\":{}}}",
+                    g.lines.0,
+                    g.lines.1,
+                    items(&g.badges),
+                    anomaly(&g.anomaly)
+                )
+            })
+            .collect::<Vec<String>>();
+        format!(
+            "{{\"rucksacks\":[{}],\"groups\":[{}]}}\n",
+            sacks.join(","),
+            groups.join(",")
+        )
+    }
+
+    /// The audit as CSV, with a row for each rucksack followed by a row for each group
+    pub fn to_csv(&self) -> String {
+        let items = |items: &[char]| items.iter().collect::<String>();
+        let priorities = |items: &[char]| {
+            items
+                .iter()
+                .map(|c| get_priority(*c).to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+        let anomaly = |a: &Option<Anomaly>| {
+            a.as_ref()
+                .map_or(String::new(), |a| csv_field(&a.to_string()))
+        };
+        let mut out = String::from("kind,first_line,last_line,items,priorities,anomaly\n");
+        for s in &self.sacks {
+            out += &format!(
+                "rucksack,{},{},{},{},{}\n",
+                s.line,
+                s.line,
+                items(&s.misplaced),
+                priorities(&s.misplaced),
+                anomaly(&s.anomaly)
+            );
+        }
+        for g in &self.groups {
+            out += &format!(
+                "group,{},{},{},{},{}\n",
+                g.lines.0,
+                g.lines.1,
+                items(&g.badges),
+                priorities(&g.badges),
+                anomaly(&g.anomaly)
+            );
+        }
+        out
+    }
+}
+
+impl std::fmt::Display for Audit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let describe = |items: &[char]| {
+            items
+                .iter()
+                .map(|c| format!("{c} ({})", get_priority(*c)))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        for s in &self.sacks {
+            write!(f, "line {}: {}", s.line, describe(&s.misplaced))?;
+            match &s.anomaly {
+                Some(a) => writeln!(f, " [{a}]")?,
+                None => writeln!(f)?,
+            }
+        }
+        for g in &self.groups {
+            write!(
+                f,
+                "lines {}-{}: badge {}",
+                g.lines.0,
+                g.lines.1,
+                describe(&g.badges)
+            )?;
+            match &g.anomaly {
+                Some(a) => writeln!(f, " [{a}]")?,
+                None => writeln!(f)?,
+            }
+        }
+        writeln!(f, "anomalies: {}", self.anomalies())
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out += &format!("\\u{:04x}", u32::from(c)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// The most steps [`Layout::find_groups`] will take before giving up
+const GROUP_SEARCH_BUDGET: usize = 1_000_000;
+
+impl Layout {
+    /// Split unordered rucksacks into groups that each share exactly one item type, given as
+    /// indices into `sacks`, or `None` if there is no such grouping
+    ///
+    /// The grouping is searched for as an exact cover, always placing next the rucksack expected
+    /// to fit the fewest groups. Its candidate groups are built only as they are tried, from the
+    /// rucksacks sharing one of its item types, so there is never more than one group in hand
+    /// per placed rucksack. The search fails with an error if it takes more than
+    /// [`GROUP_SEARCH_BUDGET`] steps.
+    pub fn find_groups<S: AsRef<str>>(
+        &self,
+        sacks: &[S],
+    ) -> Result<Option<Vec<Vec<usize>>>, String> {
+        let masks = sacks
+            .iter()
+            .map(|sack| self.parse_rucksack(sack.as_ref()).map(item_mask))
+            .collect::<Result<Vec<u64>, String>>()?;
+        if !sacks.len().is_multiple_of(self.group_size) {
+            return Ok(None);
+        }
+        let mut search = GroupSearch::new(&masks, self.group_size);
+        let mut groups = Vec::new();
+        if !search.cover(&mut groups)? {
+            return Ok(None);
+        }
+        for group in &groups {
+            let members = group
+                .iter()
+                .map(|i| sacks[*i].as_ref())
+                .collect::<Vec<&str>>();
+            self.badge(&members)?;
+        }
+        Ok(Some(groups))
+    }
+}
+
+struct GroupSearch<'a> {
+    masks: &'a [u64],
+    /// The item types in each rucksack, as bit positions in its mask
+    items: Vec<Vec<usize>>,
+    size: usize,
+    /// The rucksacks holding each item type, indexed by priority minus one
+    holders: Vec<Vec<usize>>,
+    /// How many of the holders of each item type are still unplaced
+    unplaced_holders: [usize; 52],
+    placed: Vec<bool>,
+    /// How many more steps the search may take
+    budget: usize,
+}
+
+impl<'a> GroupSearch<'a> {
+    fn new(masks: &'a [u64], size: usize) -> Self {
+        let items = masks
+            .iter()
+            .map(|mask| bits(*mask).collect())
+            .collect::<Vec<Vec<usize>>>();
+        let mut holders = vec![Vec::new(); 52];
+        for (i, sack) in items.iter().enumerate() {
+            for bit in sack {
+                holders[*bit].push(i);
+            }
+        }
+        let mut unplaced_holders = [0; 52];
+        for (count, sacks) in unplaced_holders.iter_mut().zip(&holders) {
+            *count = sacks.len();
+        }
+        Self {
+            masks,
+            items,
+            size,
+            holders,
+            unplaced_holders,
+            placed: vec![false; masks.len()],
+            budget: GROUP_SEARCH_BUDGET,
+        }
+    }
+
+    /// The unplaced rucksack expected to fit the fewest groups, which is placed next
+    ///
+    /// The estimate treats the other unplaced rucksacks as holding each item type
+    /// independently: a group is formed by choosing the other members among the holders of
+    /// one of the rucksack's item types, and hoping they don't all hold any of its others.
+    fn most_constrained(&self) -> Option<usize> {
+        let unplaced = self.placed.iter().filter(|placed| !**placed).count() as f64;
+        let others = self.size as i32 - 1;
+        let mut apart = [0.0; 52];
+        for (chance, holders) in apart.iter_mut().zip(self.unplaced_holders) {
+            *chance = 1.0 - (holders as f64 / unplaced).powi(others);
+        }
+        let expected_groups = |sack: usize| {
+            let items = &self.items[sack];
+            items
+                .iter()
+                .map(|badge| {
+                    let holders = (self.unplaced_holders[*badge] - 1) as f64;
+                    let rest = items.iter().filter(|item| *item != badge);
+                    holders.powi(others) * rest.map(|item| apart[*item]).product::<f64>()
+                })
+                .sum::<f64>()
+        };
+        (0..self.placed.len())
+            .filter(|i| !self.placed[*i])
+            .map(|i| (i, expected_groups(i)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    /// Choose groups until every rucksack is placed, or undo them all and give up
+    fn cover(&mut self, chosen: &mut Vec<Vec<usize>>) -> Result<bool, String> {
+        let Some(next) = self.most_constrained() else {
+            return Ok(true);
+        };
+        let mut badges = self.items[next].clone();
+        badges.sort_by_key(|bit| self.unplaced_holders[*bit]);
+        for badge in badges {
+            if self.extend(&mut vec![next], self.masks[next], badge, 0, chosen)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Add holders of the `badge` from the `start`th on to the `group`, whose members have the
+    /// items in `common`, and carry on covering once it is full with only the `badge` in common
+    fn extend(
+        &mut self,
+        group: &mut Vec<usize>,
+        common: u64,
+        badge: usize,
+        start: usize,
+        chosen: &mut Vec<Vec<usize>>,
+    ) -> Result<bool, String> {
+        if self.budget == 0 {
+            return Err(format!(
+                "gave up looking for groups after {GROUP_SEARCH_BUDGET} steps"
+            ));
+        }
+        self.budget -= 1;
+        if group.len() == self.size {
+            if common != 1 << badge {
+                return Ok(false);
+            }
+            self.place(group, true);
+            chosen.push(group.clone());
+            if self.cover(chosen)? {
+                return Ok(true);
+            }
+            chosen.pop();
+            self.place(group, false);
+            return Ok(false);
+        }
+        for i in start..self.holders[badge].len() {
+            let sack = self.holders[badge][i];
+            if self.placed[sack] || group.contains(&sack) {
+                continue;
+            }
+            group.push(sack);
+            let found = self.extend(group, common & self.masks[sack], badge, i + 1, chosen)?;
+            group.pop();
+            if found {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Place, or unplace, every rucksack in the group
+    fn place(&mut self, group: &[usize], place: bool) {
+        for member in group {
+            self.placed[*member] = place;
+            for bit in &self.items[*member] {
+                if place {
+                    self.unplaced_holders[*bit] -= 1;
+                } else {
+                    self.unplaced_holders[*bit] += 1;
+                }
+            }
+        }
+    }
+}
+
+/// The positions of the set bits in the `mask`, from lowest to highest
+fn bits(mask: u64) -> impl Iterator<Item = usize> {
+    (0..64).filter(move |bit| mask & 1 << bit != 0)
+}
+
+/// Same as [`process_group`], but tracks which sacks hold each item type in a `HashMap`
+pub fn process_group_naive<S: AsRef<str>>(sacks: &[S]) -> Result<u32, String> {
+    if sacks.len() != 3 {
+        return Err(format!(
+            "expected a group of 3 rucksacks, got {}",
+            sacks.len()
+        ));
+    }
     let mut hash = HashMap::<char, [bool; 3]>::new();
     for (i, sack) in sacks.iter().enumerate() {
-        for c in sack.chars() {
-            let entry = hash.entry(c).or_insert(Default::default());
+        for c in parse_rucksack(sack.as_ref())?.chars() {
+            let entry = hash.entry(c).or_default();
             entry[i] = true;
         }
     }
     let badge = hash
         .into_iter()
         .find(|(_, check)| check.iter().all(|s| *s))
-        .expect("a badge with count 3");
-    get_priority(badge.0)
+        .ok_or_else(|| "no badge common to the group".to_string())?;
+    Ok(get_priority(badge.0))
+}
+
+/// The set of item types in `items`, with the item of priority `p` at bit `p - 1`
+///
+/// The items must have been checked by [`parse_rucksack`].
+fn item_mask(items: &str) -> u64 {
+    items
+        .chars()
+        .fold(0, |mask, c| mask | 1 << (get_priority(c) - 1))
 }
 
 fn get_priority(c: char) -> u32 {
@@ -70,3 +742,113 @@ fn test_priority() {
     assert_eq!(get_priority('a'), 1);
     assert_eq!(get_priority('A'), 27);
 }
+
+#[test]
+fn test_priorities_are_consecutive() {
+    let items = ('a'..='z').chain('A'..='Z');
+    for (expected, c) in (1..=52).zip(items) {
+        assert_eq!(get_priority(c), expected, "priority of {c}");
+        assert_eq!(item(expected), c);
+    }
+}
+
+#[test]
+fn test_masks_match_naive() {
+    use crate::{generate, utils::Rng};
+
+    let input = generate::day_03(&mut Rng::new(39), 300);
+    assert_eq!(part_1(input.as_bytes()), part_1_naive(input.as_bytes()));
+    assert_eq!(part_2(input.as_bytes()), part_2_naive(input.as_bytes()));
+    // Rucksacks with several misplaced items are still scored, by the first one found:
+    assert_eq!(part_1("abcbca\n".as_bytes()), 2);
+    assert_eq!(part_1_naive("abcbca\n".as_bytes()), 2);
+    assert_eq!(item_mask("aZ"), 1 | 1 << 51);
+    assert_eq!(single_item(item_mask("bb")), Ok(2));
+    assert!(single_item(0).is_err());
+}
+
+#[test]
+fn test_layout() {
+    let layout = Layout::new(3, 2).unwrap();
+    assert_eq!(layout.misplaced("abcAbCbBa"), Ok(2));
+    assert!(layout.misplaced("abcAbC").is_err());
+    assert!(layout.misplaced("abAB").is_err());
+    assert_eq!(
+        layout.compartments_of("").collect::<Vec<&str>>(),
+        ["", "", ""]
+    );
+    assert_eq!(
+        layout.misplaced(""),
+        Err("rucksack \"\" has no item in every compartment".into())
+    );
+    assert_eq!(
+        layout.misplaced("abcabcabc"),
+        Err("rucksack \"abcabcabc\" has several items (a, b, c) in every compartment".into())
+    );
+    assert_eq!(layout.sum_badges("abcAbC\nbBD\n".as_bytes()), Ok(2));
+    assert!(layout.sum_badges("abc\nabc\n".as_bytes()).is_err());
+    assert!(layout.sum_badges("abc\nbcd\nb".as_bytes()).is_err());
+    assert!(Layout::new(1, 3).is_err());
+    assert!(Layout::new(2, 0).is_err());
+}
+
+#[test]
+fn test_audit() {
+    let audit = Layout::default()
+        .audit("vJrwpWtwJgWrhcsFMMfFFhFp\nabab\nab,c\nabcd\nefgh\n".as_bytes())
+        .unwrap();
+    let anomalies = audit
+        .sacks
+        .iter()
+        .map(|s| s.anomaly.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(audit.sacks[0].misplaced, ['p']);
+    assert_eq!(audit.sacks[1].misplaced, ['a', 'b']);
+    assert_eq!(anomalies[1], Some(Anomaly::SeveralSharedItems));
+    assert!(matches!(anomalies[2], Some(Anomaly::Invalid(_))));
+    assert_eq!(anomalies[3], Some(Anomaly::NoSharedItem));
+    assert_eq!(audit.groups[0].anomaly, Some(Anomaly::InvalidMember));
+    assert_eq!(audit.groups[1].lines, (4, 5));
+    assert_eq!(audit.groups[1].anomaly, Some(Anomaly::IncompleteGroup));
+    assert_eq!(audit.anomalies(), 6);
+    assert!(audit.to_json().contains(r#"{"item":"p","priority":16}"#));
+    assert!(audit
+        .to_csv()
+        .contains(r#"rucksack,3,3,,,"invalid item ',' in rucksack ""ab,c"""#));
+
+    // A blank line has no items at all, so nothing is misplaced:
+    let audit = Layout::default().audit("\n".as_bytes()).unwrap();
+    assert_eq!(audit.sacks[0].misplaced, []);
+    assert_eq!(audit.sacks[0].anomaly, Some(Anomaly::NoSharedItem));
+}
+
+#[test]
+fn test_find_groups() {
+    use crate::{generate, utils::Rng};
+
+    let input = generate::day_03(&mut Rng::new(42), 30);
+    let mut sacks = input.lines().collect::<Vec<&str>>();
+    Rng::new(0).shuffle(&mut sacks);
+    let layout = Layout::default();
+    let groups = layout.find_groups(&sacks).unwrap().unwrap();
+    assert_eq!(groups.len(), 10);
+    let mut members = groups.concat();
+    members.sort();
+    assert_eq!(members, (0..30).collect::<Vec<usize>>());
+
+    // `cd` has nothing in common with the other rucksacks, which share two items anyway:
+    assert_eq!(
+        layout.find_groups(&["ab", "ab", "ab", "ab", "ab", "cd"]),
+        Ok(None)
+    );
+    assert_eq!(layout.find_groups(&["ab", "ab"]), Ok(None));
+    assert!(layout.find_groups(&["ab", "a"]).is_err());
+
+    // Large inputs have a vast number of possible groups, so they are only built as needed:
+    let input = generate::day_03(&mut Rng::new(1), 1500);
+    let mut sacks = input.lines().collect::<Vec<&str>>();
+    Rng::new(1).shuffle(&mut sacks);
+    assert_eq!(layout.find_groups(&sacks).unwrap().unwrap().len(), 500);
+    // Every way of trying to group these fails, and there are too many to try them all:
+    assert!(layout.find_groups(&["ab"; 3000]).is_err());
+}
@@ -1,10 +1,18 @@
-use std::str::FromStr;
+use std::{io::BufRead, str::FromStr};
 
-use crate::utils::get_lines;
+use crate::utils::get_buf_reader;
 
 pub fn answer_part_1() -> u32 {
+    part_1(get_buf_reader("input/day_04.txt"))
+}
+
+pub fn answer_part_2() -> u32 {
+    part_2(get_buf_reader("input/day_04.txt"))
+}
+
+pub fn part_1<R: BufRead>(input: R) -> u32 {
     let mut sum = 0;
-    for line in get_lines("input/day_04.txt").map(Result::unwrap) {
+    for line in input.lines().map(Result::unwrap) {
         let rp = line.parse::<RangePair>().expect("parses range pair");
         if rp.0.contains(&rp.1) || rp.1.contains(&rp.0) {
             sum += 1;
@@ -13,9 +21,9 @@ pub fn answer_part_1() -> u32 {
     sum
 }
 
-pub fn answer_part_2() -> u32 {
+pub fn part_2<R: BufRead>(input: R) -> u32 {
     let mut sum = 0;
-    for line in get_lines("input/day_04.txt").map(Result::unwrap) {
+    for line in input.lines().map(Result::unwrap) {
         let rp = line.parse::<RangePair>().expect("parses range pair");
         if rp.0.overlaps(&rp.1) {
             sum += 1;
@@ -24,34 +32,143 @@ pub fn answer_part_2() -> u32 {
     sum
 }
 
-struct RangePair(SectionRange, SectionRange);
+pub struct RangePair(SectionRange, SectionRange);
+
+impl RangePair {
+    pub fn ranges(&self) -> [SectionRange; 2] {
+        [self.0, self.1]
+    }
+}
 
 impl FromStr for RangePair {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut split = s.split(',');
-        Ok(Self(
+        let rp = Self(
             split
                 .next()
-                .ok_or_else(|| format!("did not contain first of pair"))?
+                .ok_or_else(|| "did not contain first of pair".to_string())?
                 .parse()
                 .map_err(|e| format!("unparseable first of pair: {e}"))?,
             split
                 .next()
-                .ok_or_else(|| format!("did not contain second of pair"))?
+                .ok_or_else(|| "did not contain second of pair".to_string())?
                 .parse()
                 .map_err(|e| format!("unparseable second of pair: {e}"))?,
-        ))
+        );
+        if split.next().is_some() {
+            return Err("contained more than a pair".to_string());
+        }
+        Ok(rp)
     }
 }
 
-struct SectionRange {
+/// The assignments of a cleaning group of any size, written as comma-separated ranges
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssignmentGroup(pub Vec<SectionRange>);
+
+impl FromStr for AssignmentGroup {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .enumerate()
+            .map(|(i, range)| {
+                range
+                    .parse()
+                    .map_err(|e| format!("unparseable range {}: {e}", i + 1))
+            })
+            .collect::<Result<Vec<SectionRange>, String>>()
+            .map(Self)
+    }
+}
+
+impl AssignmentGroup {
+    /// The elves, by index, whose whole assignment is also assigned to someone else in the group
+    pub fn redundant(&self) -> Vec<usize> {
+        (0..self.0.len())
+            .filter(|i| {
+                let others = self
+                    .0
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| j != i)
+                    .map(|(_, range)| *range)
+                    .collect::<SectionSet>();
+                SectionSet::from(self.0[*i]).difference(&others).is_empty()
+            })
+            .collect()
+    }
+
+    /// The fewest elves, by index, that between them still cover every section assigned to the
+    /// group
+    ///
+    /// Working from the lowest section up, each step picks the elf reaching furthest among
+    /// those starting at or before the first section not yet covered, which is optimal.
+    pub fn minimal_cover(&self) -> Vec<usize> {
+        let mut order = (0..self.0.len()).collect::<Vec<usize>>();
+        order.sort_by_key(|i| self.0[*i]);
+        let mut cover = Vec::new();
+        let mut next = 0;
+        // The first section that is not yet covered, if any remain:
+        let mut uncovered = order.first().map(|i| self.0[*i].start);
+        while let Some(section) = uncovered {
+            let mut best: Option<usize> = None;
+            while let Some(i) = order.get(next).copied() {
+                if self.0[i].start > section {
+                    break;
+                }
+                if best.is_none_or(|b| self.0[i].end > self.0[b].end) {
+                    best = Some(i);
+                }
+                next += 1;
+            }
+            // The first uncovered section is always the start of, or inside, some assignment:
+            let best = best.expect("an elf covers the section");
+            cover.push(best);
+            let reached = self.0[best].end;
+            uncovered = reached.checked_add(1).and_then(|after| {
+                order[next..]
+                    .iter()
+                    .map(|i| self.0[*i])
+                    .filter(|r| r.end >= after)
+                    .map(|r| r.start.max(after))
+                    .min()
+            });
+        }
+        cover.sort_unstable();
+        cover
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SectionRange {
     start: usize,
     end: usize,
 }
 
 impl SectionRange {
+    pub fn new(start: usize, end: usize) -> Result<Self, String> {
+        if start > end {
+            return Err(format!("start {start} is after end {end}"));
+        }
+        Ok(Self { start, end })
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// The number of sections in the range
+    pub fn size(&self) -> usize {
+        self.end - self.start + 1
+    }
+
     fn contains(&self, other: &Self) -> bool {
         self.start <= other.start && self.end >= other.end
     }
@@ -66,17 +183,595 @@ impl FromStr for SectionRange {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut split = s.split('-');
-        Ok(Self {
+        let sr = Self {
             start: split
                 .next()
-                .ok_or_else(|| format!("unable to extract start"))?
+                .ok_or_else(|| "unable to extract start".to_string())?
                 .parse()
                 .map_err(|e| format!("unparseable start: {e}"))?,
             end: split
                 .next()
-                .ok_or_else(|| format!("unable to extract end"))?
+                .ok_or_else(|| "unable to extract end".to_string())?
                 .parse()
                 .map_err(|e| format!("unparseable end: {e}"))?,
+        };
+        if split.next().is_some() {
+            return Err("contained more than a start and end".to_string());
+        }
+        Self::new(sr.start, sr.end)
+    }
+}
+
+/// An elf's assignment, identified by its line and its side of the pair, counting from one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Assignment {
+    pub line: usize,
+    pub side: usize,
+    pub range: SectionRange,
+}
+
+impl std::fmt::Display for Assignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {} side {}: {}-{}",
+            self.line, self.side, self.range.start, self.range.end
+        )
+    }
+}
+
+/// Read every elf's assignment, from lines with any number of ranges
+pub fn read_assignments<R: BufRead>(input: R) -> Result<Vec<Assignment>, String> {
+    let mut assignments = Vec::new();
+    for (n, line) in input.lines().enumerate() {
+        let line = line.map_err(|e| format!("failed to read line: {e}"))?;
+        let group = line
+            .parse::<AssignmentGroup>()
+            .map_err(|e| format!("line {}: {e}", n + 1))?;
+        for (side, range) in group.0.into_iter().enumerate() {
+            assignments.push(Assignment {
+                line: n + 1,
+                side: side + 1,
+                range,
+            });
+        }
+    }
+    Ok(assignments)
+}
+
+/// Split the sections from the first assigned to the last into runs covered by the same number
+/// of elves, found by sweeping across the starts and ends of the assignments
+pub fn depth_profile<I>(assignments: I) -> Vec<(SectionRange, usize)>
+where
+    I: IntoIterator<Item = SectionRange>,
+{
+    // Each assignment adds one elf at its start, and removes it again after its end:
+    let mut events = Vec::new();
+    for range in assignments {
+        events.push((range.start, 1));
+        if let Some(after) = range.end.checked_add(1) {
+            events.push((after, -1));
+        }
+    }
+    events.sort_unstable();
+    let mut profile = Vec::new();
+    let mut depth = 0_isize;
+    let mut i = 0;
+    while i < events.len() {
+        let section = events[i].0;
+        while events.get(i).is_some_and(|e| e.0 == section) {
+            depth += events[i].1;
+            i += 1;
+        }
+        let end = match events.get(i) {
+            Some((next, _)) => next - 1,
+            None if depth > 0 => usize::MAX,
+            None => break,
+        };
+        profile.push((
+            SectionRange {
+                start: section,
+                end,
+            },
+            depth as usize,
+        ));
+    }
+    profile
+}
+
+/// Answers which assignments cover a section, or overlap a range, without comparing against
+/// every assignment
+///
+/// The assignments are sorted by start and laid out as an implicit balanced search tree, with the
+/// middle of each slice at its root. Each root also records the latest end in its subtree, so
+/// subtrees that end before a query can be skipped.
+#[derive(Debug, Clone)]
+pub struct AssignmentIndex {
+    assignments: Vec<Assignment>,
+    max_end: Vec<usize>,
+}
+
+impl AssignmentIndex {
+    pub fn new(mut assignments: Vec<Assignment>) -> Self {
+        assignments.sort_by_key(|a| a.range);
+        let mut index = Self {
+            max_end: vec![0; assignments.len()],
+            assignments,
+        };
+        index.build(0, index.assignments.len());
+        index
+    }
+
+    fn build(&mut self, lo: usize, hi: usize) -> usize {
+        if lo >= hi {
+            return 0;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let left = self.build(lo, mid);
+        let right = self.build(mid + 1, hi);
+        self.max_end[mid] = self.assignments[mid].range.end.max(left).max(right);
+        self.max_end[mid]
+    }
+
+    pub fn len(&self) -> usize {
+        self.assignments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assignments.is_empty()
+    }
+
+    /// The assignments covering the `section`, in order of line and side
+    pub fn covering(&self, section: usize) -> Vec<Assignment> {
+        self.overlapping(&SectionRange {
+            start: section,
+            end: section,
         })
     }
+
+    /// The assignments sharing at least one section with the `range`, in order of line and side
+    pub fn overlapping(&self, range: &SectionRange) -> Vec<Assignment> {
+        let mut found = Vec::new();
+        self.search(0, self.assignments.len(), range, &mut found);
+        found.sort_by_key(|a| (a.line, a.side));
+        found
+    }
+
+    fn search(&self, lo: usize, hi: usize, range: &SectionRange, found: &mut Vec<Assignment>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        if self.max_end[mid] < range.start {
+            return;
+        }
+        self.search(lo, mid, range, found);
+        let assignment = &self.assignments[mid];
+        // Everything to the right starts no earlier than this:
+        if assignment.range.start > range.end {
+            return;
+        }
+        if assignment.range.overlaps(range) {
+            found.push(*assignment);
+        }
+        self.search(mid + 1, hi, range, found);
+    }
+
+    pub fn depth_profile(&self) -> Vec<(SectionRange, usize)> {
+        depth_profile(self.assignments.iter().map(|a| a.range))
+    }
+}
+
+/// A set of sections, stored as sorted ranges that neither overlap nor touch
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SectionSet {
+    ranges: Vec<SectionRange>,
+}
+
+impl From<SectionRange> for SectionSet {
+    fn from(range: SectionRange) -> Self {
+        Self {
+            ranges: vec![range],
+        }
+    }
+}
+
+impl FromIterator<SectionRange> for SectionSet {
+    fn from_iter<I: IntoIterator<Item = SectionRange>>(iter: I) -> Self {
+        let mut sorted = iter.into_iter().collect::<Vec<SectionRange>>();
+        sorted.sort();
+        let mut ranges: Vec<SectionRange> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            match ranges.last_mut() {
+                Some(last) if range.start <= last.end.saturating_add(1) => {
+                    last.end = last.end.max(range.end);
+                }
+                _ => ranges.push(range),
+            }
+        }
+        Self { ranges }
+    }
+}
+
+impl SectionSet {
+    pub fn ranges(&self) -> &[SectionRange] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The total number of sections in the set
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(SectionRange::size).sum()
+    }
+
+    pub fn contains(&self, section: usize) -> bool {
+        let i = self.ranges.partition_point(|r| r.end < section);
+        self.ranges.get(i).is_some_and(|r| r.start <= section)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges.iter().chain(&other.ranges).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let (start, end) = (a.start.max(b.start), a.end.min(b.end));
+            if start <= end {
+                ranges.push(SectionRange { start, end });
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// The sections in `self` but not in `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for a in &self.ranges {
+            while other.ranges.get(j).is_some_and(|b| b.end < a.start) {
+                j += 1;
+            }
+            let mut next = Some(a.start);
+            for b in other.ranges[j..].iter().take_while(|b| b.start <= a.end) {
+                let Some(start) = next else { break };
+                if b.start > start {
+                    ranges.push(SectionRange {
+                        start,
+                        end: b.start - 1,
+                    });
+                }
+                next = b.end.checked_add(1).filter(|n| *n <= a.end);
+            }
+            if let Some(start) = next {
+                ranges.push(SectionRange { start, end: a.end });
+            }
+        }
+        Self { ranges }
+    }
+
+    /// The sections missing between the first and last sections of the set
+    pub fn gaps(&self) -> Self {
+        let ranges = self
+            .ranges
+            .windows(2)
+            .map(|w| SectionRange {
+                start: w[0].end + 1,
+                end: w[1].start - 1,
+            })
+            .collect();
+        Self { ranges }
+    }
+}
+
+impl std::fmt::Display for SectionSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|r| format!("{}-{}", r.start, r.end))
+            .collect::<Vec<String>>();
+        write!(f, "{{{}}}", ranges.join(", "))
+    }
+}
+
+/// The sections between the first and last assigned sections, split by how many elves are
+/// assigned to them: `by_count[k]` holds the sections covered by exactly `k` elves
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Coverage {
+    pub by_count: Vec<SectionSet>,
+}
+
+impl Coverage {
+    pub fn new(assignments: &[SectionRange]) -> Self {
+        let mut by_count: Vec<Vec<SectionRange>> = Vec::new();
+        for (range, depth) in depth_profile(assignments.iter().copied()) {
+            if by_count.len() <= depth {
+                by_count.resize(depth + 1, Vec::new());
+            }
+            by_count[depth].push(range);
+        }
+        Self {
+            by_count: by_count.into_iter().map(SectionSet::from_iter).collect(),
+        }
+    }
+
+    /// The sections covered by exactly `count` elves
+    pub fn exactly(&self, count: usize) -> SectionSet {
+        self.by_count.get(count).cloned().unwrap_or_default()
+    }
+
+    /// The sections covered by more than `count` elves
+    pub fn more_than(&self, count: usize) -> SectionSet {
+        self.by_count
+            .iter()
+            .skip(count + 1)
+            .flat_map(|set| set.ranges.iter().copied())
+            .collect()
+    }
+}
+
+/// Draw each line's assignments as bars over a shared section axis, like the puzzle's
+/// illustrations
+///
+/// Sections assigned to just one elf of a line are drawn `=`, and sections they share with
+/// another elf of the same line `#`. When the sections don't fit in `width` columns, each column
+/// stands for several sections, and is drawn as assigned if any of them are.
+pub fn gantt(lines: &[(usize, AssignmentGroup)], width: usize) -> String {
+    let ranges = || lines.iter().flat_map(|(_, group)| group.0.iter());
+    let (Some(lo), Some(hi)) = (
+        ranges().map(|r| r.start).min(),
+        ranges().map(|r| r.end).max(),
+    ) else {
+        return String::new();
+    };
+    let scale = (hi - lo) / width.max(1) + 1;
+    let columns = (hi - lo) / scale + 1;
+    let column = |c: usize| {
+        let start = lo + c * scale;
+        SectionRange {
+            start,
+            end: start.saturating_add(scale - 1).min(hi),
+        }
+    };
+    let labels = lines
+        .iter()
+        .flat_map(|(n, group)| (1..=group.0.len()).map(move |side| format!("{n}.{side}")))
+        .collect::<Vec<String>>();
+    let margin = labels.iter().map(String::len).max().unwrap_or(0) + 1;
+
+    let mut out = format!("sections {lo}-{hi}, {scale} per column\n");
+    let mut axis = String::new();
+    let mut ruler = String::new();
+    for c in 0..columns {
+        if c % 10 == 0 {
+            if axis.len() <= c {
+                axis += &" ".repeat(c - axis.len());
+                axis += &column(c).start.to_string();
+            }
+            ruler.push('|');
+        } else {
+            ruler.push('-');
+        }
+    }
+    out += &format!("{:margin$}{axis}\n{:margin$}{ruler}\n", "", "");
+    let mut labels = labels.into_iter();
+    for (i, (_, group)) in lines.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        for (side, range) in group.0.iter().enumerate() {
+            let others = group
+                .0
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != side)
+                .map(|(_, r)| *r)
+                .collect::<SectionSet>();
+            let bar = (0..columns)
+                .map(|c| {
+                    let col = column(c);
+                    if !range.overlaps(&col) {
+                        return '.';
+                    }
+                    let assigned = SectionRange {
+                        start: range.start.max(col.start),
+                        end: range.end.min(col.end),
+                    };
+                    if others.intersection(&assigned.into()).is_empty() {
+                        '='
+                    } else {
+                        '#'
+                    }
+                })
+                .collect::<String>();
+            let label = labels.next().expect("a label for every assignment");
+            out += &format!("{label:<margin$}{bar} {}-{}\n", range.start, range.end);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+fn random_range(rng: &mut crate::utils::Rng) -> SectionRange {
+    let start = rng.range(0, 20);
+    SectionRange {
+        start,
+        end: rng.range(start, 20),
+    }
+}
+
+#[test]
+fn test_overlaps_is_symmetric() {
+    let mut rng = crate::utils::Rng::new(4);
+    for _ in 0..10_000 {
+        let (a, b) = (random_range(&mut rng), random_range(&mut rng));
+        assert_eq!(a.overlaps(&b), b.overlaps(&a));
+        assert!(a.overlaps(&a) && a.contains(&a));
+    }
+}
+
+#[test]
+fn test_contains_implies_overlaps() {
+    let mut rng = crate::utils::Rng::new(4);
+    for _ in 0..10_000 {
+        let (a, b) = (random_range(&mut rng), random_range(&mut rng));
+        if a.contains(&b) {
+            assert!(a.overlaps(&b) && b.overlaps(&a));
+        }
+        if a.contains(&b) && b.contains(&a) {
+            assert_eq!((a.start, a.end), (b.start, b.end));
+        }
+    }
+}
+
+#[test]
+fn test_range_pair_parses_generated_line() {
+    let mut rng = crate::utils::Rng::new(4);
+    for _ in 0..1_000 {
+        let (a, b) = (random_range(&mut rng), random_range(&mut rng));
+        let line = format!("{}-{},{}-{}", a.start, a.end, b.start, b.end);
+        let rp = line.parse::<RangePair>().unwrap();
+        assert_eq!((rp.0.start, rp.0.end), (a.start, a.end));
+        assert_eq!((rp.1.start, rp.1.end), (b.start, b.end));
+    }
+}
+
+#[test]
+fn test_section_set_algebra() {
+    use std::collections::BTreeSet;
+
+    let sections = |set: &SectionSet| {
+        set.ranges
+            .iter()
+            .flat_map(|r| r.start..=r.end)
+            .collect::<BTreeSet<usize>>()
+    };
+    let mut rng = crate::utils::Rng::new(43);
+    for _ in 0..1_000 {
+        let a = (0..rng.range(0, 4))
+            .map(|_| random_range(&mut rng))
+            .collect::<SectionSet>();
+        let b = (0..rng.range(0, 4))
+            .map(|_| random_range(&mut rng))
+            .collect::<SectionSet>();
+        let (sa, sb) = (sections(&a), sections(&b));
+        assert_eq!(sections(&a.union(&b)), &sa | &sb);
+        assert_eq!(sections(&a.intersection(&b)), &sa & &sb);
+        assert_eq!(sections(&a.difference(&b)), &sa - &sb);
+        assert_eq!(a.len(), sa.len());
+        assert!((0..=21).all(|s| a.contains(s) == sa.contains(&s)));
+        // Normalised sets are equal exactly when they hold the same sections:
+        assert_eq!(a.union(&b), b.union(&a));
+        let span = sa
+            .first()
+            .zip(sa.last())
+            .map_or(BTreeSet::new(), |(f, l)| (*f..=*l).collect());
+        assert_eq!(sections(&a.gaps()), &span - &sa);
+    }
+}
+
+#[test]
+fn test_coverage() {
+    let assignments = "2-4,6-8\n2-3,4-5\n5-7,7-9\n12-12,3-3\n".as_bytes();
+    let ranges = read_assignments(assignments)
+        .unwrap()
+        .into_iter()
+        .map(|a| a.range)
+        .collect::<Vec<SectionRange>>();
+    let coverage = Coverage::new(&ranges);
+    assert_eq!(coverage.exactly(0).to_string(), "{10-11}");
+    assert_eq!(coverage.exactly(1).to_string(), "{9-9, 12-12}");
+    assert_eq!(coverage.exactly(2).to_string(), "{2-2, 4-6, 8-8}");
+    assert_eq!(coverage.more_than(2).to_string(), "{3-3, 7-7}");
+}
+
+#[test]
+fn test_assignment_index() {
+    let mut rng = crate::utils::Rng::new(44);
+    let input = crate::generate::day_04(&mut rng, 500);
+    let assignments = read_assignments(input.as_bytes()).unwrap();
+    let index = AssignmentIndex::new(assignments.clone());
+    for _ in 0..200 {
+        let range = random_range(&mut rng);
+        let expected = assignments
+            .iter()
+            .filter(|a| a.range.overlaps(&range))
+            .copied()
+            .collect::<Vec<Assignment>>();
+        assert_eq!(index.overlapping(&range), expected);
+    }
+    let profile = index.depth_profile();
+    for section in 1..=99 {
+        let (_, depth) = profile
+            .iter()
+            .find(|(r, _)| r.start <= section && section <= r.end)
+            .unwrap();
+        assert_eq!(index.covering(section).len(), *depth);
+    }
+}
+
+#[test]
+fn test_assignment_groups() {
+    let group = "1-4,3-6,5-9,2-8,12-12".parse::<AssignmentGroup>().unwrap();
+    assert_eq!(group.redundant(), [1, 3]);
+    assert_eq!(group.minimal_cover(), [0, 2, 4]);
+    assert!("1-4,,5-9".parse::<AssignmentGroup>().is_err());
+
+    // The cover is as small as any, and covers everything, checked against every subset:
+    let mut rng = crate::utils::Rng::new(45);
+    for _ in 0..500 {
+        let group = AssignmentGroup(
+            (0..rng.range(1, 6))
+                .map(|_| random_range(&mut rng))
+                .collect(),
+        );
+        let union = |elves: &[usize]| elves.iter().map(|i| group.0[*i]).collect::<SectionSet>();
+        let everyone = (0..group.0.len()).collect::<Vec<usize>>();
+        let cover = group.minimal_cover();
+        assert_eq!(union(&cover), union(&everyone));
+        let smallest = (0..1_usize << group.0.len())
+            .map(|bits| {
+                everyone
+                    .iter()
+                    .copied()
+                    .filter(|i| bits & 1 << i != 0)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|elves| union(elves) == union(&everyone))
+            .map(|elves| elves.len())
+            .min();
+        assert_eq!(Some(cover.len()), smallest);
+    }
+}
+
+#[test]
+fn test_gantt() {
+    let lines = ["2-4,6-8", "5-7,7-9"]
+        .iter()
+        .enumerate()
+        .map(|(n, line)| (n + 1, line.parse::<AssignmentGroup>().unwrap()))
+        .collect::<Vec<_>>();
+    let expected = "\
+sections 2-9, 1 per column
+    2
+    |-------
+1.1 ===..... 2-4
+1.2 ....===. 6-8
+
+2.1 ...==#.. 5-7
+2.2 .....#== 7-9
+";
+    assert_eq!(gantt(&lines, 80), expected);
+    // Squeezed into four columns, each covering two sections:
+    assert!(gantt(&lines, 4).contains("2.1 .=#. 5-7\n"));
 }
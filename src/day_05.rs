@@ -1,11 +1,12 @@
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     collections::{BTreeMap, VecDeque},
+    io::BufRead,
     ops::{Deref, DerefMut},
     str::FromStr,
 };
 
-use crate::utils::get_lines;
+use crate::utils::get_buf_reader;
 
 /*
 
@@ -24,39 +25,43 @@ use crate::utils::get_lines;
 */
 
 pub fn answer_part_1() -> String {
+    part_1(get_buf_reader("input/day_05.txt"))
+}
+
+pub fn answer_part_2() -> String {
+    part_2(get_buf_reader("input/day_05.txt"))
+}
+
+pub fn part_1<R: BufRead>(input: R) -> String {
     let mut cargo_bay = CargoBay::new_9000();
-    for line in get_lines("input/day_05.txt").map(Result::unwrap) {
-        if !cargo_bay.process_input_line(&line) {
-            continue;
-        }
-    }
-    for line in get_lines("input/day_05.txt").map(Result::unwrap) {
-        if let Ok(instruction) = line.parse::<Instruction>() {
-            cargo_bay.move_crate(instruction);
-        }
+    let instructions = cargo_bay.load(input).expect("parses input");
+    for instruction in instructions {
+        cargo_bay
+            .move_crate(instruction)
+            .expect("valid instruction");
     }
-    cargo_bay.list_top_crates()
+    cargo_bay.top_crates()
 }
 
-pub fn answer_part_2() -> String {
+pub fn part_2<R: BufRead>(input: R) -> String {
     let mut cargo_bay = CargoBay::new_9001();
-    for line in get_lines("input/day_05.txt").map(Result::unwrap) {
-        if !cargo_bay.process_input_line(&line) {
-            continue;
-        }
+    let instructions = cargo_bay.load(input).expect("parses input");
+    for instruction in instructions {
+        cargo_bay
+            .move_crate(instruction)
+            .expect("valid instruction");
     }
-    for line in get_lines("input/day_05.txt").map(Result::unwrap) {
-        if let Ok(instruction) = line.parse::<Instruction>() {
-            cargo_bay.move_crate(instruction);
-        }
-    }
-    cargo_bay.list_top_crates()
+    cargo_bay.top_crates()
 }
 
 #[derive(Debug)]
-struct Crate(char);
+pub struct Crate(char);
 
 impl Crate {
+    pub fn label(&self) -> char {
+        self.0
+    }
+
     fn from_input(c: char) -> Option<Self> {
         match c {
             'A'..='Z' => Some(Self(c)),
@@ -65,8 +70,9 @@ impl Crate {
     }
 }
 
+/// A stack of crates, with the top crate at the front
 #[derive(Default, Debug)]
-struct CrateStack(VecDeque<Crate>);
+pub struct CrateStack(VecDeque<Crate>);
 
 impl Deref for CrateStack {
     type Target = VecDeque<Crate>;
@@ -82,73 +88,127 @@ impl DerefMut for CrateStack {
     }
 }
 
-struct CargoBay {
+pub struct CargoBay {
     stack_list: BTreeMap<usize, RefCell<CrateStack>>,
-    crane: Crane,
+    crane: Box<dyn Crane>,
+    /// Energy spent by the crane so far
+    energy: Cell<u64>,
 }
 
 impl CargoBay {
-    fn new_9000() -> Self {
+    pub fn new_9000() -> Self {
+        Self::with_crane(Box::new(CrateMover9000))
+    }
+
+    pub fn new_9001() -> Self {
+        Self::with_crane(Box::new(CrateMover9001))
+    }
+
+    pub fn with_crane(crane: Box<dyn Crane>) -> Self {
         Self {
             stack_list: Default::default(),
-            crane: Crane::NineThousand,
+            crane,
+            energy: Cell::new(0),
         }
     }
 
-    fn new_9001() -> Self {
-        Self {
-            stack_list: Default::default(),
-            crane: Crane::NineThousandOne(Default::default()),
-        }
-    }
-
-    fn move_crate(&self, instruction: Instruction) {
-        let mut from = self
-            .stack_list
-            .get(&instruction.from)
-            .expect("valid from instruction")
-            .borrow_mut();
-        let mut to = self
-            .stack_list
-            .get(&instruction.to)
-            .expect("valid to instruction")
-            .borrow_mut();
-        match self.crane {
-            Crane::NineThousand => {
-                for _ in 0..instruction.amount {
-                    to.push_front(
-                        from.pop_front()
-                            .expect("could not pop a crate off from stack"),
-                    );
-                }
-            }
-            Crane::NineThousandOne(ref buffer) => {
-                let mut buffer = buffer.borrow_mut();
-                for _ in 0..instruction.amount {
-                    buffer.push(
-                        from.pop_front()
-                            .expect("could not pop a crate off from stack"),
-                    );
-                }
-                while let Some(crt) = buffer.pop() {
-                    to.push_front(crt);
-                }
-            }
+    pub fn energy(&self) -> u64 {
+        self.energy.get()
+    }
+
+    pub fn move_crate(&self, instruction: Instruction) -> Result<(), String> {
+        let stack = |slot| {
+            self.stack_list
+                .get(&slot)
+                .ok_or_else(|| format!("no stack {slot} in the cargo bay"))
+        };
+        let (from, to) = (stack(instruction.from)?, stack(instruction.to)?);
+        if from.borrow().len() < instruction.amount {
+            return Err(format!(
+                "cannot move {} crates from stack {} holding {}",
+                instruction.amount,
+                instruction.from,
+                from.borrow().len()
+            ));
+        }
+        if instruction.from == instruction.to {
+            // Any crane puts the crates back where they came from:
+            return Ok(());
         }
+        self.crane.move_crates(
+            instruction.amount,
+            &mut from.borrow_mut(),
+            &mut to.borrow_mut(),
+        );
+        self.energy
+            .set(self.energy.get() + self.crane.cost(&instruction));
+        Ok(())
+    }
+
+    /// Carry out the `instruction` in reverse, putting the bay back as it was before it
+    pub fn undo_crate(&self, instruction: &Instruction) -> Result<(), String> {
+        let stack = |slot| {
+            self.stack_list
+                .get(&slot)
+                .ok_or_else(|| format!("no stack {slot} in the cargo bay"))
+        };
+        let (from, to) = (stack(instruction.from)?, stack(instruction.to)?);
+        if to.borrow().len() < instruction.amount {
+            return Err(format!(
+                "cannot undo \"{instruction}\" with stack {} holding {}",
+                instruction.to,
+                to.borrow().len()
+            ));
+        }
+        if instruction.from == instruction.to {
+            return Ok(());
+        }
+        self.crane.undo_move(
+            instruction.amount,
+            &mut from.borrow_mut(),
+            &mut to.borrow_mut(),
+        );
+        Ok(())
     }
 
     fn insert_crate(&mut self, slot: usize, crt: Crate) {
-        let stack = self.stack_list.entry(slot).or_insert(Default::default());
+        let stack = self.stack_list.entry(slot).or_default();
         stack.borrow_mut().push_back(crt);
     }
 
+    /// Load the drawing of the starting stacks from the `input`, and parse the list of
+    /// instructions that follows it
+    pub fn load<R: BufRead>(&mut self, input: R) -> Result<Vec<Instruction>, String> {
+        let mut lines = input.lines();
+        for line in lines.by_ref() {
+            let line = line.map_err(|e| format!("failed to read line: {e}"))?;
+            if line.is_empty() {
+                break;
+            }
+            self.process_input_line(&line);
+        }
+        lines
+            .map(|line| {
+                line.map_err(|e| format!("failed to read line: {e}"))?
+                    .parse::<Instruction>()
+            })
+            .collect()
+    }
+
     fn process_input_line(&mut self, line: &str) -> bool {
+        if line.trim_start().starts_with(|c: char| c.is_ascii_digit()) {
+            // The line of stack numbers, which makes sure stacks that start empty still exist:
+            for slot in line.split_whitespace().filter_map(|n| n.parse().ok()) {
+                self.stack_list.entry(slot).or_default();
+            }
+            return false;
+        }
         let mut crates_inserted = false;
         let slots = line
             .chars()
             .collect::<Vec<char>>()
             .chunks(4)
-            .map(|chars| Crate::from_input(chars[1]))
+            .map(|chars| chars.get(1).copied().and_then(Crate::from_input))
             .collect::<Vec<Option<Crate>>>();
         for (i, slot) in slots.into_iter().enumerate() {
             if let Some(crt) = slot {
@@ -159,15 +219,92 @@ impl CargoBay {
         crates_inserted
     }
 
-    fn list_top_crates(&self) -> String {
-        self.stack_list
-            .iter()
-            .map(|(_, stack)| stack.borrow_mut().pop_front().expect("stack not empty").0)
+    /// The highest stack number, counting the stacks in between as empty whether or not they
+    /// were seen in the input
+    fn max_slot(&self) -> usize {
+        self.stack_list.keys().max().copied().unwrap_or(0)
+    }
+
+    /// The crate on top of each stack, with a space for an empty stack
+    pub fn top_crates(&self) -> String {
+        (1..=self.max_slot())
+            .map(|slot| match self.stack_list.get(&slot) {
+                Some(stack) => stack.borrow().front().map_or(' ', |crt| crt.0),
+                None => ' ',
+            })
             .collect::<String>()
     }
+
+    /// The number of crates in each stack, by stack number
+    pub fn heights(&self) -> Vec<(usize, usize)> {
+        (1..=self.max_slot())
+            .map(|slot| {
+                let height = self.stack_list.get(&slot).map_or(0, |s| s.borrow().len());
+                (slot, height)
+            })
+            .collect()
+    }
+
+    /// The crates in a stack, from the bottom up
+    pub fn contents(&self, slot: usize) -> Option<String> {
+        let stack = self.stack_list.get(&slot)?.borrow();
+        Some(stack.iter().rev().map(|crt| crt.0).collect())
+    }
+
+    /// Every position of a crate with the given label, as its stack number and its height in
+    /// the stack, counting from one at the bottom
+    pub fn locate(&self, label: char) -> Vec<(usize, usize)> {
+        let mut found = Vec::new();
+        for (slot, stack) in &self.stack_list {
+            let stack = stack.borrow();
+            for (depth, crt) in stack.iter().enumerate() {
+                if crt.0 == label {
+                    found.push((*slot, stack.len() - depth));
+                }
+            }
+        }
+        found.sort_unstable();
+        found
+    }
+}
+
+/// Draws the stacks in the same format as the puzzle input
+impl std::fmt::Display for CargoBay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let slots = self.max_slot();
+        let stacks = (1..=slots)
+            .map(|slot| self.stack_list.get(&slot).map(|stack| stack.borrow()))
+            .collect::<Vec<_>>();
+        let height = stacks
+            .iter()
+            .flatten()
+            .map(|stack| stack.len())
+            .max()
+            .unwrap_or(0);
+        for level in (0..height).rev() {
+            let line = stacks
+                .iter()
+                .map(|stack| match stack {
+                    // Stacks are kept top first, so count down from the bottom:
+                    Some(stack) if stack.len() > level => {
+                        format!("[{}]", stack[stack.len() - 1 - level].0)
+                    }
+                    _ => "   ".to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join(" ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        let labels = (1..=slots)
+            .map(|slot| format!(" {slot} "))
+            .collect::<Vec<String>>()
+            .join(" ");
+        writeln!(f, "{}", labels.trim_end())
+    }
 }
 
-struct Instruction {
+#[derive(Debug, PartialEq, Eq)]
+pub struct Instruction {
     amount: usize,
     from: usize,
     to: usize,
@@ -183,30 +320,334 @@ impl FromStr for Instruction {
         }
         let amount = words
             .next()
-            .ok_or_else(|| format!("move amount"))?
+            .ok_or_else(|| "move amount".to_string())?
             .parse::<usize>()
-            .map_err(|_| format!("move amount is valid usize"))?;
+            .map_err(|_| "move amount is valid usize".to_string())?;
         if !matches!(words.next(), Some("from")) {
             return Err(String::from("expected from instruction"));
         }
         let from = words
             .next()
-            .ok_or_else(|| format!("from stack"))?
+            .ok_or_else(|| "from stack".to_string())?
             .parse::<usize>()
-            .map_err(|_| format!("from stack is valid usize"))?;
+            .map_err(|_| "from stack is valid usize".to_string())?;
         if !matches!(words.next(), Some("to")) {
             return Err(String::from("expected to instruction"));
         }
         let to = words
             .next()
-            .ok_or_else(|| format!("to stack"))?
+            .ok_or_else(|| "to stack".to_string())?
             .parse::<usize>()
-            .map_err(|_| format!("to stack is valid usize"))?;
+            .map_err(|_| "to stack is valid usize".to_string())?;
         Ok(Self { amount, from, to })
     }
 }
 
-enum Crane {
-    NineThousand,
-    NineThousandOne(RefCell<Vec<Crate>>),
+impl Instruction {
+    pub fn amount(&self) -> usize {
+        self.amount
+    }
+
+    pub fn from(&self) -> usize {
+        self.from
+    }
+
+    pub fn to(&self) -> usize {
+        self.to
+    }
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "move {} from {} to {}", self.amount, self.from, self.to)
+    }
+}
+
+/// A model of crane, which decides how crates are moved between stacks
+pub trait Crane {
+    /// Move `amount` crates off the top of `from` onto `to`
+    ///
+    /// The stacks are always distinct, and `from` holds at least `amount` crates.
+    fn move_crates(&self, amount: usize, from: &mut CrateStack, to: &mut CrateStack);
+
+    /// Put back the `amount` crates that [`Self::move_crates`] moved from `from` onto `to`
+    ///
+    /// The stacks are always distinct, and `to` holds at least `amount` crates.
+    fn undo_move(&self, amount: usize, from: &mut CrateStack, to: &mut CrateStack);
+
+    /// The energy spent carrying out the instruction
+    fn cost(&self, _instruction: &Instruction) -> u64 {
+        0
+    }
+}
+
+/// Moves crates one at a time, reversing their order
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn move_crates(&self, amount: usize, from: &mut CrateStack, to: &mut CrateStack) {
+        for crt in from.drain(..amount) {
+            to.push_front(crt);
+        }
+    }
+
+    fn undo_move(&self, amount: usize, from: &mut CrateStack, to: &mut CrateStack) {
+        self.move_crates(amount, to, from)
+    }
+}
+
+/// Moves crates all at once, keeping their order
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn move_crates(&self, amount: usize, from: &mut CrateStack, to: &mut CrateStack) {
+        for crt in from.drain(..amount).rev() {
+            to.push_front(crt);
+        }
+    }
+
+    fn undo_move(&self, amount: usize, from: &mut CrateStack, to: &mut CrateStack) {
+        self.move_crates(amount, to, from)
+    }
+}
+
+/// Moves at most `capacity` crates at a time, keeping the order of each lift
+pub struct LimitedCrane {
+    capacity: usize,
+}
+
+impl LimitedCrane {
+    pub fn new(capacity: usize) -> Result<Self, String> {
+        if capacity == 0 {
+            return Err("a crane must be able to lift at least one crate".to_string());
+        }
+        Ok(Self { capacity })
+    }
+}
+
+impl Crane for LimitedCrane {
+    fn move_crates(&self, amount: usize, from: &mut CrateStack, to: &mut CrateStack) {
+        let mut remaining = amount;
+        while remaining > 0 {
+            let lift = remaining.min(self.capacity);
+            CrateMover9001.move_crates(lift, from, to);
+            remaining -= lift;
+        }
+    }
+
+    fn undo_move(&self, amount: usize, from: &mut CrateStack, to: &mut CrateStack) {
+        // The last, and possibly partial, lift ended up on top, so it goes back first:
+        let mut remaining = amount;
+        let mut lift = match amount % self.capacity {
+            0 => self.capacity,
+            partial => partial,
+        };
+        while remaining > 0 {
+            CrateMover9001.move_crates(lift, to, from);
+            remaining -= lift;
+            lift = self.capacity;
+        }
+    }
+}
+
+/// Lifts crates all at once, and slides them in underneath the target stack
+pub struct BottomLoader;
+
+impl Crane for BottomLoader {
+    fn move_crates(&self, amount: usize, from: &mut CrateStack, to: &mut CrateStack) {
+        to.extend(from.drain(..amount));
+    }
+
+    fn undo_move(&self, amount: usize, from: &mut CrateStack, to: &mut CrateStack) {
+        let keep = to.len() - amount;
+        let bottom = to.split_off(keep);
+        for crt in bottom.into_iter().rev() {
+            from.push_front(crt);
+        }
+    }
+}
+
+/// Another crane, charged a fixed amount of energy for every move and for every crate moved
+pub struct MeteredCrane {
+    pub crane: Box<dyn Crane>,
+    pub per_move: u64,
+    pub per_crate: u64,
+}
+
+impl Crane for MeteredCrane {
+    fn move_crates(&self, amount: usize, from: &mut CrateStack, to: &mut CrateStack) {
+        self.crane.move_crates(amount, from, to)
+    }
+
+    fn undo_move(&self, amount: usize, from: &mut CrateStack, to: &mut CrateStack) {
+        self.crane.undo_move(amount, from, to)
+    }
+
+    fn cost(&self, instruction: &Instruction) -> u64 {
+        self.crane.cost(instruction) + self.per_move + self.per_crate * instruction.amount as u64
+    }
+}
+
+/// Find a crane model by name: `9000`, `9001`, `bottom`, or `limited:N` for a crane lifting at
+/// most `N` crates at a time
+pub fn crane_by_name(name: &str) -> Result<Box<dyn Crane>, String> {
+    match name {
+        "9000" => Ok(Box::new(CrateMover9000)),
+        "9001" => Ok(Box::new(CrateMover9001)),
+        "bottom" => Ok(Box::new(BottomLoader)),
+        _ => match name.strip_prefix("limited:") {
+            Some(capacity) => {
+                let capacity = capacity
+                    .parse()
+                    .map_err(|e| format!("invalid crane capacity {capacity:?}: {e}"))?;
+                Ok(Box::new(LimitedCrane::new(capacity)?))
+            }
+            None => Err(format!("unknown crane model: {name}")),
+        },
+    }
+}
+
+/// Work out the starting drawing from an input whose drawing shows the stacks after all of the
+/// instructions have been carried out by the named crane
+///
+/// The instructions are undone from last to first, and the result is checked by carrying them
+/// out again from the reconstructed start.
+pub fn reconstruct<R: BufRead>(input: R, model: &str) -> Result<String, String> {
+    let mut cargo_bay = CargoBay::with_crane(crane_by_name(model)?);
+    let instructions = cargo_bay.load(input)?;
+    let end = cargo_bay.to_string();
+    for instruction in instructions.iter().rev() {
+        cargo_bay.undo_crate(instruction)?;
+    }
+    let start = cargo_bay.to_string();
+
+    let mut replay = CargoBay::with_crane(crane_by_name(model)?);
+    replay.load(start.as_bytes())?;
+    for instruction in instructions {
+        replay.move_crate(instruction)?;
+    }
+    if replay.to_string() != end {
+        return Err(format!(
+            "replaying from the reconstructed start ends with\n{replay}instead of\n{end}"
+        ));
+    }
+    Ok(start)
+}
+
+#[test]
+fn test_instruction_round_trip() {
+    let mut rng = crate::utils::Rng::new(5);
+    for _ in 0..10_000 {
+        let instruction = Instruction {
+            amount: rng.range(0, 1_000),
+            from: rng.range(0, 1_000),
+            to: rng.range(0, 1_000),
+        };
+        assert_eq!(instruction.to_string().parse(), Ok(instruction));
+    }
+}
+
+#[test]
+fn test_load_rejects_malformed_input() {
+    let mut cargo_bay = CargoBay::new_9000();
+    let instructions = cargo_bay
+        .load("[A] [\n  [B\n 1   2\n\nmove 1 from 1 to 1\nmove 2 from 1 to 2\n".as_bytes())
+        .unwrap();
+    let mut results = instructions
+        .into_iter()
+        .map(|instruction| cargo_bay.move_crate(instruction));
+    assert_eq!(results.next(), Some(Ok(())));
+    assert!(matches!(results.next(), Some(Err(_))));
+    assert!(CargoBay::new_9001()
+        .load("\nmove 1 to 2".as_bytes())
+        .is_err());
+}
+
+#[test]
+fn test_drawing_round_trip() {
+    let drawing = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n";
+    let mut cargo_bay = CargoBay::new_9001();
+    let instructions = cargo_bay
+        .load(format!("{drawing}\nmove 3 from 2 to 3\n").as_bytes())
+        .unwrap();
+    assert_eq!(cargo_bay.to_string(), drawing);
+    for instruction in instructions {
+        cargo_bay.move_crate(instruction).unwrap();
+    }
+    assert_eq!(
+        cargo_bay.to_string(),
+        "        [D]\n        [C]\n[N]     [M]\n[Z]     [P]\n 1   2   3\n"
+    );
+}
+
+#[test]
+fn test_queries_leave_the_bay_intact() {
+    let mut cargo_bay = CargoBay::new_9000();
+    let instructions = cargo_bay
+        .load("    [D]\n[N] [C]\n[Z] [M]\n 1   2   3\n\nmove 1 from 2 to 3\n".as_bytes())
+        .unwrap();
+    assert_eq!(cargo_bay.top_crates(), "ND ");
+    assert_eq!(cargo_bay.top_crates(), "ND ");
+    assert_eq!(cargo_bay.heights(), [(1, 2), (2, 3), (3, 0)]);
+    for instruction in instructions {
+        cargo_bay.move_crate(instruction).unwrap();
+    }
+    assert_eq!(cargo_bay.top_crates(), "NCD");
+    assert_eq!(cargo_bay.contents(2).as_deref(), Some("MC"));
+    assert_eq!(cargo_bay.contents(4), None);
+    assert_eq!(cargo_bay.locate('D'), [(3, 1)]);
+    assert_eq!(cargo_bay.locate('Q'), []);
+
+    // Without a line of stack numbers, an empty stack between two others is still counted:
+    let mut cargo_bay = CargoBay::new_9000();
+    cargo_bay.load("[A]     [B]\n".as_bytes()).unwrap();
+    assert_eq!(cargo_bay.top_crates(), "A B");
+    assert_eq!(cargo_bay.heights(), [(1, 1), (2, 0), (3, 1)]);
+}
+
+#[test]
+fn test_crane_models() {
+    let run = |crane: Box<dyn Crane>| {
+        let mut cargo_bay = CargoBay::with_crane(crane);
+        let instructions = cargo_bay
+            .load("[A]\n[B]\n[C] [X]\n[D] [Y]\n 1   2\n\nmove 3 from 1 to 2\n".as_bytes())
+            .unwrap();
+        for instruction in instructions {
+            cargo_bay.move_crate(instruction).unwrap();
+        }
+        (cargo_bay.contents(2).unwrap(), cargo_bay.energy())
+    };
+    // Stack contents are listed from the bottom up:
+    assert_eq!(run(crane_by_name("9000").unwrap()).0, "YXABC");
+    assert_eq!(run(crane_by_name("9001").unwrap()).0, "YXCBA");
+    assert_eq!(run(crane_by_name("limited:2").unwrap()).0, "YXBAC");
+    assert_eq!(run(crane_by_name("bottom").unwrap()).0, "CBAYX");
+    assert!(crane_by_name("limited:0").is_err());
+    let metered = MeteredCrane {
+        crane: Box::new(CrateMover9000),
+        per_move: 10,
+        per_crate: 2,
+    };
+    assert_eq!(run(Box::new(metered)), ("YXABC".to_string(), 16));
+}
+
+#[test]
+fn test_reconstruct() {
+    for model in ["9000", "9001", "bottom", "limited:2", "limited:3"] {
+        for seed in 0..10 {
+            let input = crate::generate::day_05(&mut crate::utils::Rng::new(seed), 30);
+            let mut cargo_bay = CargoBay::with_crane(crane_by_name(model).unwrap());
+            let instructions = cargo_bay.load(input.as_bytes()).unwrap();
+            let start = cargo_bay.to_string();
+            let moves = instructions
+                .iter()
+                .map(|i| format!("{i}\n"))
+                .collect::<String>();
+            for instruction in instructions {
+                cargo_bay.move_crate(instruction).unwrap();
+            }
+            let end = format!("{cargo_bay}\n{moves}");
+            assert_eq!(reconstruct(end.as_bytes(), model), Ok(start), "{model}");
+        }
+    }
 }
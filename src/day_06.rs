@@ -1,41 +1,48 @@
 use std::{
     collections::{HashSet, VecDeque},
-    io::Read,
+    io::BufRead,
 };
 
 use crate::utils::get_buf_reader;
 
 pub fn answer_part_1() -> usize {
-    let buf_reader = get_buf_reader("input/day_06.txt");
-    let mut sig_buffer = SignalBuffer::<4>::new();
-    let mut offset = 1;
-    for c in buf_reader
-        .bytes()
-        .map(Result::unwrap)
-        .map(|byte| char::from(byte))
-    {
-        sig_buffer.push(c);
-        if sig_buffer.is_marker() {
-            return offset;
-        } else {
-            offset += 1;
-        }
-    }
-
-    offset
+    part_1(get_buf_reader("input/day_06.txt"))
 }
 
 pub fn answer_part_2() -> usize {
-    let buf_reader = get_buf_reader("input/day_06.txt");
-    let mut sig_buffer = SignalBuffer::<14>::new();
+    part_2(get_buf_reader("input/day_06.txt"))
+}
+
+pub fn part_1<R: BufRead>(input: R) -> usize {
+    find_marker::<4, _>(input)
+}
+
+pub fn part_2<R: BufRead>(input: R) -> usize {
+    find_marker::<14, _>(input)
+}
+
+/// Find the number of characters that must be read before the first marker of `SIZE`
+/// distinct characters has been seen
+pub fn find_marker<const SIZE: usize, R: BufRead>(input: R) -> usize {
+    find_marker_with::<SIZE, R>(input, SignalBuffer::is_marker)
+}
+
+/// Same as [`find_marker`], but checks for a marker by comparing every character in the buffer
+///
+/// This is kept as an oracle for testing [`find_marker`].
+pub fn find_marker_naive<const SIZE: usize, R: BufRead>(input: R) -> usize {
+    find_marker_with::<SIZE, R>(input, SignalBuffer::is_marker_naive)
+}
+
+fn find_marker_with<const SIZE: usize, R: BufRead>(
+    input: R,
+    is_marker: fn(&SignalBuffer<SIZE>) -> bool,
+) -> usize {
+    let mut sig_buffer = SignalBuffer::<SIZE>::new();
     let mut offset = 1;
-    for c in buf_reader
-        .bytes()
-        .map(Result::unwrap)
-        .map(|b| char::from(b))
-    {
+    for c in input.bytes().map(Result::unwrap).map(char::from) {
         sig_buffer.push(c);
-        if sig_buffer.is_marker() {
+        if is_marker(&sig_buffer) {
             return offset;
         } else {
             offset += 1;
@@ -45,37 +52,55 @@ pub fn answer_part_2() -> usize {
     offset
 }
 
-struct SignalBuffer<const SIZE: usize>(VecDeque<char>);
+struct SignalBuffer<const SIZE: usize> {
+    chars: VecDeque<char>,
+    /// The number of times each character occurs in the buffer; characters are read from single
+    /// bytes, so there are only 256 of them
+    counts: [usize; 256],
+    /// The number of characters that occur more than once in the buffer
+    repeated: usize,
+}
 
 impl<const SIZE: usize> SignalBuffer<SIZE> {
     fn new() -> Self {
-        Self(VecDeque::with_capacity(SIZE))
+        Self {
+            chars: VecDeque::with_capacity(SIZE),
+            counts: [0; 256],
+            repeated: 0,
+        }
     }
 
     fn is_full(&self) -> bool {
-        self.0.len() == SIZE
+        self.chars.len() == SIZE
     }
 
     fn push(&mut self, c: char) {
         if self.is_full() {
-            self.0.pop_back();
-            self.0.push_front(c);
-        } else {
-            self.0.push_front(c);
+            let old = self.chars.pop_back().expect("full buffer is not empty");
+            let count = &mut self.counts[old as usize];
+            *count -= 1;
+            if *count == 1 {
+                self.repeated -= 1;
+            }
         }
+        let count = &mut self.counts[c as usize];
+        *count += 1;
+        if *count == 2 {
+            self.repeated += 1;
+        }
+        self.chars.push_front(c);
     }
 
     fn is_marker(&self) -> bool {
-        if self.0.len() < SIZE {
+        self.is_full() && self.repeated == 0
+    }
+
+    fn is_marker_naive(&self) -> bool {
+        if self.chars.len() < SIZE {
             return false;
         }
-        // TODO - could we not allocate a new HashSet every time we call this function?
-        // It may be better to house the hash set in the SignalBuffer, and evict keys
-        // as elements are popped from the dequeue. However, in that case, a hash set
-        // may not be sufficient, as it would need to account for there being multiples
-        // of a given character.
         let mut hash = HashSet::new();
-        for c in self.0.iter() {
+        for c in self.chars.iter() {
             if !hash.insert(c) {
                 return false;
             }
@@ -1,16 +1,25 @@
-use crate::utils::get_lines;
+use std::io::BufRead;
+
+use crate::utils::get_buf_reader;
 
 const TOTAL_FS_SPACE: u64 = 70_000_000;
 const DESIRED_SPACE: u64 = 30_000_000;
 
 pub fn answer_part_1() -> (u64, u64) {
+    part_1(get_buf_reader("input/day_07.txt"))
+}
+
+pub fn answer_part_2() -> u64 {
+    part_2(get_buf_reader("input/day_07.txt"))
+}
+
+/// Sum the sizes of the directories of at most 100,000, and also report the total used space
+pub fn part_1<R: BufRead>(input: R) -> (u64, u64) {
     let mut result = 0;
     let mut used_space = 0;
     let mut stack = Vec::new();
 
-    for line in get_lines("input/day_07.txt").map(Result::unwrap) {
-        let tokens = tokenize(&line);
-        let p = Line::parse(&tokens);
+    for p in parse(input) {
         match p {
             Line::Command(cmd) => match cmd {
                 Command::CdInto => stack.push(0),
@@ -42,15 +51,16 @@ pub fn answer_part_1() -> (u64, u64) {
     (result, used_space)
 }
 
-pub fn answer_part_2() -> u64 {
-    let (_, used_space) = answer_part_1();
+pub fn part_2<R: BufRead>(mut input: R) -> u64 {
+    // The transcript is read twice, first to find the used space:
+    let mut transcript = String::new();
+    input.read_to_string(&mut transcript).expect("reads input");
+    let (_, used_space) = part_1(transcript.as_bytes());
     let unused_space = TOTAL_FS_SPACE - used_space;
     let min_size = DESIRED_SPACE - unused_space;
     let mut result = u64::MAX;
     let mut stack = Vec::new();
-    for line in get_lines("input/day_07.txt").map(Result::unwrap) {
-        let tokens = tokenize(&line);
-        let p = Line::parse(&tokens);
+    for p in parse(transcript.as_bytes()) {
         match p {
             Line::Command(cmd) => match cmd {
                 Command::CdInto => stack.push(0),
@@ -74,6 +84,13 @@ pub fn answer_part_2() -> u64 {
     result
 }
 
+fn parse<R: BufRead>(input: R) -> impl Iterator<Item = Line> {
+    input
+        .lines()
+        .map(Result::unwrap)
+        .map(|line| parse_line(&line).expect("parses line"))
+}
+
 #[derive(Debug)]
 enum Token {
     Dollar,
@@ -99,24 +116,29 @@ fn tokenize(s: &str) -> Vec<Token> {
         .collect()
 }
 
-enum Line {
+/// Parse a line of the terminal output
+pub fn parse_line(line: &str) -> Result<Line, String> {
+    Line::parse(&tokenize(line))
+}
+
+pub enum Line {
     Command(Command),
     Node(Node),
 }
 
 impl Line {
-    fn parse(tokens: &[Token]) -> Self {
+    fn parse(tokens: &[Token]) -> Result<Self, String> {
         if let Ok(cmd) = Command::parse(tokens) {
-            Self::Command(cmd)
+            Ok(Self::Command(cmd))
         } else if let Ok(node) = Node::parse(tokens) {
-            Self::Node(node)
+            Ok(Self::Node(node))
         } else {
-            panic!("unparseable line: {tokens:?}")
+            Err(format!("unparseable line: {tokens:?}"))
         }
     }
 }
 
-enum Command {
+pub enum Command {
     CdInto,
     CdOut,
     Ls,
@@ -145,14 +167,14 @@ enum CommandParseError {
     InvalidCommand,
 }
 
-enum Node {
+pub enum Node {
     Dir,
     File { size: u64 },
 }
 
 impl Node {
     fn parse(tokens: &[Token]) -> Result<Self, NodeParseError> {
-        match (tokens.get(0), tokens.get(1)) {
+        match (tokens.first(), tokens.get(1)) {
             (Some(Token::Dir), Some(Token::Value(_))) => Ok(Node::Dir),
             (Some(Token::Value(s)), Some(Token::Value(_))) => s
                 .parse::<u64>()
@@ -1,9 +1,46 @@
-use std::{collections::HashMap, ops::BitAnd, path::Path};
+use std::{collections::HashMap, io::BufRead, ops::BitAnd};
 
-use crate::utils::get_lines;
+use crate::utils::get_buf_reader;
 
 pub fn answer_part_1() -> u64 {
-    let tp = TreePatch::from_file("input/day_08.txt");
+    part_1(get_buf_reader("input/day_08.txt"))
+}
+
+pub fn answer_part_2() -> u64 {
+    part_2(get_buf_reader("input/day_08.txt"))
+}
+
+pub fn part_1<R: BufRead>(input: R) -> u64 {
+    count_visible(&parse(input).expect("parses forest"))
+}
+
+pub fn part_2<R: BufRead>(input: R) -> u64 {
+    best_score(input)
+}
+
+/// Parse a forest of tree heights, one row per line
+pub fn parse<R: BufRead>(input: R) -> Result<TreePatch<u8>, String> {
+    TreePatch::from_reader(input)
+}
+
+/// The best scenic score in the forest
+pub fn best_score<R: BufRead>(input: R) -> u64 {
+    TreePatch::from_reader(input)
+        .expect("parses forest")
+        .score()
+}
+
+/// Same as [`best_score`], but walks out from every tree in each direction
+///
+/// This is kept as an oracle for testing [`best_score`].
+pub fn best_score_naive<R: BufRead>(input: R) -> u64 {
+    TreePatch::from_reader(input)
+        .expect("parses forest")
+        .score_naive()
+}
+
+/// Count the trees that are visible from outside the forest
+pub fn count_visible(tp: &TreePatch<u8>) -> u64 {
     let hidden = tp.row_wise_forward()
         & tp.row_wise_backward()
         & tp.col_wise_forward()
@@ -18,12 +55,7 @@ pub fn answer_part_1() -> u64 {
     result
 }
 
-pub fn answer_part_2() -> u64 {
-    let tp = TreePatch::from_file("input/day_08.txt");
-    tp.score()
-}
-
-struct TreePatch<T> {
+pub struct TreePatch<T> {
     grid: HashMap<(usize, usize), T>,
     size: (usize, usize),
 }
@@ -43,7 +75,7 @@ impl std::fmt::Display for TreePatch<bool> {
                     write!(f, "0 ")?;
                 }
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         Ok(())
     }
@@ -111,34 +143,40 @@ impl<T> TreePatch<T> {
     }
 }
 
-fn char_to_u8(c: char) -> u8 {
+fn char_to_u8(c: char) -> Result<u8, String> {
     match c {
-        '0' => 0,
-        '1' => 1,
-        '2' => 2,
-        '3' => 3,
-        '4' => 4,
-        '5' => 5,
-        '6' => 6,
-        '7' => 7,
-        '8' => 8,
-        '9' => 9,
-        _ => panic!("invalid char {c}"),
+        '0' => Ok(0),
+        '1' => Ok(1),
+        '2' => Ok(2),
+        '3' => Ok(3),
+        '4' => Ok(4),
+        '5' => Ok(5),
+        '6' => Ok(6),
+        '7' => Ok(7),
+        '8' => Ok(8),
+        '9' => Ok(9),
+        _ => Err(format!("invalid char {c:?}")),
     }
 }
 
 impl TreePatch<u8> {
-    fn from_file<P: AsRef<Path>>(path: P) -> Self {
+    fn from_reader<R: BufRead>(reader: R) -> Result<Self, String> {
         let mut tp = TreePatch::new();
-        for (i, line) in get_lines(path).map(Result::unwrap).enumerate() {
+        let mut width = None;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| format!("failed to read line: {e}"))?;
+            let expected = *width.get_or_insert(line.chars().count());
+            if line.chars().count() != expected {
+                return Err(format!("row {i} does not have {expected} trees"));
+            }
             for (j, c) in line.chars().enumerate() {
-                tp.insert((i, j), char_to_u8(c));
+                tp.insert((i, j), char_to_u8(c)?);
             }
         }
-        tp
+        Ok(tp)
     }
 
-    fn row_wise_forward(&self) -> TreePatch<bool> {
+    pub fn row_wise_forward(&self) -> TreePatch<bool> {
         let mut tp = TreePatch::<bool>::new();
         tp.size = self.size;
         for i in 0..self.n_rows() {
@@ -150,7 +188,7 @@ impl TreePatch<u8> {
         tp
     }
 
-    fn row_wise_backward(&self) -> TreePatch<bool> {
+    pub fn row_wise_backward(&self) -> TreePatch<bool> {
         let mut tp = TreePatch::<bool>::new();
         tp.size = self.size;
         for i in 0..self.n_rows() {
@@ -162,7 +200,7 @@ impl TreePatch<u8> {
         tp
     }
 
-    fn col_wise_forward(&self) -> TreePatch<bool> {
+    pub fn col_wise_forward(&self) -> TreePatch<bool> {
         let mut tp = TreePatch::<bool>::new();
         tp.size = self.size;
         for j in 0..self.n_cols() {
@@ -174,7 +212,7 @@ impl TreePatch<u8> {
         tp
     }
 
-    fn col_wise_backward(&self) -> TreePatch<bool> {
+    pub fn col_wise_backward(&self) -> TreePatch<bool> {
         let mut tp = TreePatch::<bool>::new();
         tp.size = self.size;
         for j in 0..self.n_cols() {
@@ -186,7 +224,40 @@ impl TreePatch<u8> {
         tp
     }
 
-    fn score(&self) -> u64 {
+    /// Compute the best scenic score in the [`TreePatch`]
+    ///
+    /// Each row and column is swept once in each direction, keeping track of where the last tree
+    /// of each height was seen, so the viewing distance from any tree is found without walking
+    /// out from it.
+    pub fn score(&self) -> u64 {
+        let (n_rows, n_cols) = (self.n_rows(), self.n_cols());
+        let mut heights = vec![vec![0; n_cols]; n_rows];
+        for ((i, j), h) in &self.grid {
+            heights[*i][*j] = *h;
+        }
+        let mut scores = vec![vec![1u64; n_cols]; n_rows];
+        let mut sweep = |cells: &mut dyn Iterator<Item = (usize, usize)>| {
+            // The position along the sweep at which a tree of each height was last seen:
+            let mut last_seen = [None; 10];
+            for (k, (i, j)) in cells.enumerate() {
+                let h = usize::from(heights[i][j]);
+                let blocker = last_seen[h..].iter().flatten().max();
+                scores[i][j] *= blocker.map_or(k, |b| k - b) as u64;
+                last_seen[h] = Some(k);
+            }
+        };
+        for i in 0..n_rows {
+            sweep(&mut (0..n_cols).map(|j| (i, j)));
+            sweep(&mut (0..n_cols).rev().map(|j| (i, j)));
+        }
+        for j in 0..n_cols {
+            sweep(&mut (0..n_rows).map(|i| (i, j)));
+            sweep(&mut (0..n_rows).rev().map(|i| (i, j)));
+        }
+        scores.into_iter().flatten().max().unwrap_or(0)
+    }
+
+    pub fn score_naive(&self) -> u64 {
         let mut best = 0;
         for ((i, j), this) in &self.grid {
             if *i == 0 || *j == 0 || *i == self.n_rows() - 1 || *j == self.n_cols() - 1 {
@@ -248,7 +319,7 @@ impl BitAnd for TreePatch<bool> {
     fn bitand(mut self, rhs: Self) -> Self::Output {
         for ((i, j), this) in self.grid.iter_mut() {
             let other = rhs.grid.get(&(*i, *j)).unwrap_or(&false);
-            *this = *this & other;
+            *this &= other;
         }
         self
     }
@@ -273,9 +344,7 @@ impl<'a, T> Iterator for RowIter<'a, T> {
 
 impl<'a, T> DoubleEndedIterator for RowIter<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        let Some(col_back) = self.col_back else {
-            return None;
-        };
+        let col_back = self.col_back?;
         self.col_back = col_back.checked_sub(1);
         self.tree_patch.grid.get(&(self.row, col_back))
     }
@@ -309,9 +378,7 @@ impl<'a, T> Iterator for ColIter<'a, T> {
 
 impl<'a, T> DoubleEndedIterator for ColIter<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        let Some(row_back) = self.row_back else {
-            return None;
-        };
+        let row_back = self.row_back?;
         self.row_back = row_back.checked_sub(1);
         self.tree_patch.grid.get(&(row_back, self.col))
     }
@@ -325,3 +392,11 @@ impl<'a, T> ExactSizeIterator for ColIter<'a, T> {
         }
     }
 }
+
+#[test]
+fn test_parse_rejects_malformed_forest() {
+    assert!(parse("123\n4a6\n".as_bytes()).is_err());
+    assert!(parse("123\n4567\n".as_bytes()).is_err());
+    assert!(parse("123\n45\n".as_bytes()).is_err());
+    assert!(parse("123\n456\n".as_bytes()).is_ok());
+}
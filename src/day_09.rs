@@ -1,20 +1,28 @@
-use std::{collections::HashSet, str::FromStr};
+use std::{collections::HashSet, io::BufRead, str::FromStr};
 
-use crate::utils::get_lines;
+use crate::utils::get_buf_reader;
 
 pub fn answer_part_1() -> usize {
+    part_1(get_buf_reader("input/day_09.txt"))
+}
+
+pub fn answer_part_2() -> usize {
+    part_2(get_buf_reader("input/day_09.txt"))
+}
+
+pub fn part_1<R: BufRead>(input: R) -> usize {
     let mut rope = Rope::new(2);
-    for line in get_lines("input/day_09.txt").map(Result::unwrap) {
-        let instruction = Instruction::from_str(&line);
+    for line in input.lines().map(Result::unwrap) {
+        let instruction = line.parse::<Instruction>().expect("parses instruction");
         rope.move_to(instruction);
     }
     rope.count_visited(1)
 }
 
-pub fn answer_part_2() -> usize {
+pub fn part_2<R: BufRead>(input: R) -> usize {
     let mut rope = Rope::new(10);
-    for line in get_lines("input/day_09.txt").map(Result::unwrap) {
-        let instruction = Instruction::from_str(&line);
+    for line in input.lines().map(Result::unwrap) {
+        let instruction = line.parse::<Instruction>().expect("parses instruction");
         rope.move_to(instruction);
     }
     rope.count_visited(9)
@@ -102,24 +110,32 @@ impl Knot {
 }
 
 #[derive(Debug)]
-struct Instruction {
+pub struct Instruction {
     direction: Direction,
     steps: usize,
 }
 
-impl Instruction {
-    fn from_str(s: &str) -> Self {
+impl FromStr for Instruction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut split = s.split_whitespace();
-        let direction = match split.next().expect("direction instruction") {
+        let direction = match split.next().ok_or("missing direction instruction")? {
             "U" => Direction::Up,
             "D" => Direction::Down,
             "L" => Direction::Left,
             "R" => Direction::Right,
-            d => panic!("unexpected direction instruction: {d}"),
+            d => return Err(format!("unexpected direction instruction: {d}")),
         };
-        let steps = usize::from_str(split.next().expect("steps instruction"))
-            .expect("steps is not a number");
-        Self { direction, steps }
+        let steps = split
+            .next()
+            .ok_or("missing steps instruction")?
+            .parse::<usize>()
+            .map_err(|e| format!("steps is not a number: {e}"))?;
+        if split.next().is_some() {
+            return Err(format!("unexpected trailing input in instruction: {s}"));
+        }
+        Ok(Self { direction, steps })
     }
 }
 
@@ -130,3 +146,38 @@ enum Direction {
     Left,
     Right,
 }
+
+#[test]
+fn test_knots_stay_adjacent() {
+    let mut rng = crate::utils::Rng::new(9);
+    let directions = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+    for size in 2..=10 {
+        let mut rope = Rope::new(size);
+        for _ in 0..1_000 {
+            rope.move_to(Instruction {
+                direction: *rng.choose(&directions),
+                steps: 1,
+            });
+            for pair in rope.0.windows(2) {
+                let (leader, follower) = (&pair[0], &pair[1]);
+                let distance = leader
+                    .x
+                    .abs_diff(follower.x)
+                    .max(leader.y.abs_diff(follower.y));
+                assert!(
+                    distance <= 1,
+                    "knot at ({}, {}) is not adjacent to its leader at ({}, {})",
+                    follower.x,
+                    follower.y,
+                    leader.x,
+                    leader.y
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! Differential testing of optimised solvers against their straightforward counterparts.
+//!
+//! Each [`Check`] pairs a naive solver, kept around as an oracle, with the optimised solver that
+//! is used to produce the answers. Both are run over many generated inputs, and the first input
+//! on which they disagree is shrunk down to a minimal counterexample.
+
+use crate::{day_02, day_03, day_06, day_08, generate, utils::Rng};
+
+pub struct Check {
+    pub name: &'static str,
+    pub day: u8,
+    generate: fn(&mut Rng, usize) -> String,
+    naive: fn(&str) -> String,
+    optimised: fn(&str) -> String,
+    /// Produce smaller variants of an input, which must still be valid inputs
+    shrink: fn(&str) -> Vec<String>,
+}
+
+pub const CHECKS: &[Check] = &[
+    Check {
+        name: "day 2 table scorer, part 1",
+        day: 2,
+        generate: generate::day_02,
+        naive: |s| day_02::part_1(s.as_bytes()).to_string(),
+        optimised: |s| score_bytes(s, day_02::Strategy::Weapon),
+        shrink: shrink_lines,
+    },
+    Check {
+        name: "day 2 table scorer, part 2",
+        day: 2,
+        generate: generate::day_02,
+        naive: |s| day_02::part_2(s.as_bytes()).to_string(),
+        optimised: |s| score_bytes(s, day_02::Strategy::Outcome),
+        shrink: shrink_lines,
+    },
+    Check {
+        name: "day 3 compartment masks",
+        day: 3,
+        generate: generate::day_03,
+        naive: |s| day_03::part_1_naive(s.as_bytes()).to_string(),
+        optimised: |s| day_03::part_1(s.as_bytes()).to_string(),
+        shrink: shrink_lines,
+    },
+    Check {
+        name: "day 3 badge masks",
+        day: 3,
+        generate: generate::day_03,
+        naive: |s| day_03::part_2_naive(s.as_bytes()).to_string(),
+        optimised: |s| day_03::part_2(s.as_bytes()).to_string(),
+        shrink: shrink_groups,
+    },
+    Check {
+        name: "day 6 start-of-packet marker",
+        day: 6,
+        generate: generate_signal,
+        naive: |s| day_06::find_marker_naive::<4, _>(s.as_bytes()).to_string(),
+        optimised: |s| day_06::find_marker::<4, _>(s.as_bytes()).to_string(),
+        shrink: shrink_chars,
+    },
+    Check {
+        name: "day 6 start-of-message marker",
+        day: 6,
+        generate: generate_signal,
+        naive: |s| day_06::find_marker_naive::<14, _>(s.as_bytes()).to_string(),
+        optimised: |s| day_06::find_marker::<14, _>(s.as_bytes()).to_string(),
+        shrink: shrink_chars,
+    },
+    Check {
+        name: "day 8 best scenic score",
+        day: 8,
+        generate: generate::day_08,
+        naive: |s| day_08::best_score_naive(s.as_bytes()).to_string(),
+        optimised: |s| day_08::best_score(s.as_bytes()).to_string(),
+        shrink: shrink_square,
+    },
+];
+
+#[derive(Debug)]
+pub struct Disagreement {
+    pub check: &'static str,
+    /// The seed of the generated input that first exposed the disagreement
+    pub seed: u64,
+    /// The shrunk counterexample
+    pub input: String,
+    pub naive: String,
+    pub optimised: String,
+}
+
+impl std::fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} disagrees on input from seed {}:",
+            self.check, self.seed
+        )?;
+        writeln!(f, "{}", self.input.trim_end())?;
+        writeln!(f, "naive: {}", self.naive)?;
+        write!(f, "optimised: {}", self.optimised)
+    }
+}
+
+impl Check {
+    /// Run the check over `cases` inputs of up to `max_size`, generated from consecutive seeds
+    /// starting at `seed`
+    pub fn run(&self, cases: u64, seed: u64, max_size: usize) -> Result<(), Disagreement> {
+        for seed in seed..seed + cases {
+            let mut rng = Rng::new(seed);
+            let size = rng.range(1, max_size.max(1));
+            let input = (self.generate)(&mut rng, size);
+            if self.disagrees(&input) {
+                let input = self.shrink(input);
+                return Err(Disagreement {
+                    check: self.name,
+                    seed,
+                    naive: (self.naive)(&input),
+                    optimised: (self.optimised)(&input),
+                    input,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn disagrees(&self, input: &str) -> bool {
+        (self.naive)(input) != (self.optimised)(input)
+    }
+
+    /// Greedily replace the input with the first smaller variant that still exposes a
+    /// disagreement, until none of them do
+    fn shrink(&self, mut input: String) -> String {
+        'shrinking: loop {
+            for candidate in (self.shrink)(&input) {
+                if self.disagrees(&candidate) {
+                    input = candidate;
+                    continue 'shrinking;
+                }
+            }
+            return input;
+        }
+    }
+}
+
+fn score_bytes(input: &str, strategy: day_02::Strategy) -> String {
+    day_02::score_bytes(input.as_bytes(), strategy).map_or_else(|e| e, |s| s.to_string())
+}
+
+fn generate_signal(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(15);
+    let offset = rng.range(1, size - 14);
+    generate::day_06(rng, size, offset).expect("offset is in range")
+}
+
+/// Remove runs of characters, from halves of the input down to single characters
+fn shrink_chars(input: &str) -> Vec<String> {
+    let chars = input.chars().collect::<Vec<char>>();
+    let mut candidates = Vec::new();
+    let mut run = chars.len() / 2;
+    while run > 0 {
+        for start in (0..chars.len()).step_by(run) {
+            let end = (start + run).min(chars.len());
+            candidates.push(chars[..start].iter().chain(&chars[end..]).collect());
+        }
+        run /= 2;
+    }
+    candidates
+}
+
+/// Remove single lines
+fn shrink_lines(input: &str) -> Vec<String> {
+    let lines = input.lines().collect::<Vec<&str>>();
+    (0..lines.len())
+        .map(|k| {
+            lines
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != k)
+                .map(|(_, line)| format!("{line}\n"))
+                .collect()
+        })
+        .collect()
+}
+
+/// Remove whole groups of three lines
+fn shrink_groups(input: &str) -> Vec<String> {
+    let lines = input.lines().collect::<Vec<&str>>();
+    let groups = lines.chunks(3).collect::<Vec<&[&str]>>();
+    (0..groups.len())
+        .map(|k| {
+            groups
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != k)
+                .flat_map(|(_, group)| group.iter().map(|line| format!("{line}\n")))
+                .collect()
+        })
+        .collect()
+}
+
+/// Remove the `k`th row and column together, so a square grid stays square
+fn shrink_square(input: &str) -> Vec<String> {
+    let rows = input.lines().collect::<Vec<&str>>();
+    (0..rows.len())
+        .map(|k| {
+            rows.iter()
+                .enumerate()
+                .filter(|(i, _)| *i != k)
+                .map(|(_, row)| {
+                    let mut row = row.chars().collect::<Vec<char>>();
+                    if k < row.len() {
+                        row.remove(k);
+                    }
+                    row.into_iter().chain(Some('\n')).collect::<String>()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_checks_agree() {
+    for check in CHECKS {
+        if let Err(d) = check.run(50, 0, 40) {
+            panic!("{d}");
+        }
+    }
+}
+
+#[test]
+fn test_shrinks_to_minimal_counterexample() {
+    let check = Check {
+        name: "broken",
+        day: 0,
+        generate: generate::day_02,
+        naive: |s| s.len().to_string(),
+        optimised: |s| (s.len() + s.matches('C').count()).to_string(),
+        shrink: shrink_chars,
+    };
+    let d = check.run(10, 0, 100).unwrap_err();
+    assert_eq!(d.input, "C");
+}
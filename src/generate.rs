@@ -0,0 +1,318 @@
+//! Random puzzle input generators.
+//!
+//! Each generator produces text in the same format as the real puzzle input for its day, so
+//! the output can be fed straight into the solvers. The `size` parameter scales the input; what
+//! it counts depends on the day (elves, rounds, rucksacks, moves, and so on).
+
+use std::fmt::Write;
+
+use crate::utils::Rng;
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub seed: u64,
+    pub size: usize,
+    /// Position of the start-of-packet marker for day 6; defaults to half of `size`
+    pub offset: Option<usize>,
+}
+
+pub fn generate(day: u8, options: &Options) -> Result<String, String> {
+    let mut rng = Rng::new(options.seed);
+    let size = options.size;
+    match day {
+        1 => Ok(day_01(&mut rng, size)),
+        2 => Ok(day_02(&mut rng, size)),
+        3 => Ok(day_03(&mut rng, size)),
+        4 => Ok(day_04(&mut rng, size)),
+        5 => Ok(day_05(&mut rng, size)),
+        6 => day_06(&mut rng, size, options.offset.unwrap_or(size / 2)),
+        7 => Ok(day_07(&mut rng, size)),
+        8 => Ok(day_08(&mut rng, size)),
+        9 => Ok(day_09(&mut rng, size)),
+        d => Err(format!("no generator for day {d}")),
+    }
+}
+
+/// `size` elves, each carrying between one and fifteen food items
+pub fn day_01(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for elf in 0..size {
+        if elf > 0 {
+            out.push('\n');
+        }
+        for _ in 0..rng.range(1, 15) {
+            writeln!(out, "{}", rng.range(1_000, 60_000)).unwrap();
+        }
+    }
+    out
+}
+
+/// `size` rounds of the strategy guide
+pub fn day_02(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::with_capacity(size * 4);
+    for _ in 0..size {
+        let theirs = rng.choose(&['A', 'B', 'C']);
+        let mine = rng.choose(&['X', 'Y', 'Z']);
+        writeln!(out, "{theirs} {mine}").unwrap();
+    }
+    out
+}
+
+const ITEMS: &[u8; 52] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// At least `size` rucksacks, rounded up to whole groups of three
+///
+/// The two compartments of every rucksack share exactly one item type, and every group of three
+/// consecutive rucksacks shares exactly one item type, its badge.
+pub fn day_03(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size.div_ceil(3) {
+        let mut items = ITEMS.to_vec();
+        rng.shuffle(&mut items);
+        let badge = items.pop().unwrap();
+        // Split the remaining 51 item types between the three rucksacks, so that nothing other
+        // than the badge can be common to the whole group:
+        for pool in items.chunks(17) {
+            let (shared, rest) = pool.split_first().unwrap();
+            let (left_pool, right_pool) = rest.split_at(rest.len() / 2);
+            let len = rng.range(4, 16);
+            let mut left = vec![*shared];
+            let mut right = vec![*shared];
+            if rng.bool() {
+                left.push(badge);
+            } else {
+                right.push(badge);
+            }
+            while left.len() < len {
+                left.push(*rng.choose(left_pool));
+            }
+            while right.len() < len {
+                right.push(*rng.choose(right_pool));
+            }
+            rng.shuffle(&mut left);
+            rng.shuffle(&mut right);
+            out.extend(left.into_iter().chain(right).map(char::from));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// `size` pairs of section assignments
+pub fn day_04(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let (a, b) = section_range(rng);
+        let (c, d) = section_range(rng);
+        writeln!(out, "{a}-{b},{c}-{d}").unwrap();
+    }
+    out
+}
+
+fn section_range(rng: &mut Rng) -> (usize, usize) {
+    let start = rng.range(1, 99);
+    (start, rng.range(start, 99))
+}
+
+/// A drawing of nine stacks followed by `size` moves
+///
+/// Moves never take the bottom crate of a stack, so every stack still has a crate on top once
+/// all of the moves have been carried out.
+pub fn day_05(rng: &mut Rng, size: usize) -> String {
+    const STACKS: usize = 9;
+    let mut heights = (0..STACKS).map(|_| rng.range(2, 8)).collect::<Vec<usize>>();
+    let tallest = *heights.iter().max().unwrap();
+    let mut out = String::new();
+    for level in (0..tallest).rev() {
+        let mut line = String::new();
+        for height in &heights {
+            if *height > level {
+                write!(line, "[{}] ", char::from(*rng.choose(&ITEMS[26..]))).unwrap();
+            } else {
+                line.push_str("    ");
+            }
+        }
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+    let labels = (1..=STACKS)
+        .map(|i| format!(" {i} "))
+        .collect::<Vec<String>>()
+        .join(" ");
+    writeln!(out, "{labels}").unwrap();
+    out.push('\n');
+    for _ in 0..size {
+        let from = loop {
+            let from = rng.range(0, STACKS - 1);
+            if heights[from] > 1 {
+                break from;
+            }
+        };
+        let to = (from + rng.range(1, STACKS - 1)) % STACKS;
+        let amount = rng.range(1, heights[from] - 1);
+        heights[from] -= amount;
+        heights[to] += amount;
+        writeln!(out, "move {amount} from {} to {}", from + 1, to + 1).unwrap();
+    }
+    out
+}
+
+/// A signal `size` characters long whose first start-of-packet marker ends `offset + 4`
+/// characters in, and whose first start-of-message marker ends `offset + 14` characters in
+pub fn day_06(rng: &mut Rng, size: usize, offset: usize) -> Result<String, String> {
+    if offset == 0 || offset + 14 > size {
+        return Err(format!(
+            "offset must be in 1..={} for a signal of size {size}",
+            size.saturating_sub(14)
+        ));
+    }
+    let mut letters = ITEMS[..26].to_vec();
+    rng.shuffle(&mut letters);
+    let marker = &letters[..14];
+    // Any window of four drawn from only three letters must contain a repeat, so there can be no
+    // marker in the prefix. Ending the prefix on the first letter of the marker prevents a
+    // marker from straddling the boundary.
+    let prefix = [marker[0], letters[14], letters[15]];
+    let mut out = String::with_capacity(size + 1);
+    for _ in 0..offset - 1 {
+        out.push(char::from(*rng.choose(&prefix)));
+    }
+    out.push(char::from(marker[0]));
+    out.extend(marker.iter().copied().map(char::from));
+    while out.len() < size {
+        out.push(char::from(*rng.choose(&letters)));
+    }
+    out.push('\n');
+    Ok(out)
+}
+
+/// A terminal transcript exploring a file system with `size` directories
+///
+/// File sizes are scaled so the total used space leaves less than the 30,000,000 needed for the
+/// update on a 70,000,000 disk, i.e., there is always a directory worth deleting.
+pub fn day_07(rng: &mut Rng, size: usize) -> String {
+    let n_dirs = size.max(1);
+    let mut children = vec![Vec::new(); n_dirs];
+    for dir in 1..n_dirs {
+        children[rng.range(0, dir - 1)].push(dir);
+    }
+    let mut files = (0..n_dirs)
+        .map(|_| {
+            (0..rng.range(0, 4))
+                .map(|_| rng.range(1_000, 300_000) as u64)
+                .collect::<Vec<u64>>()
+        })
+        .collect::<Vec<Vec<u64>>>();
+    let raw_total = files.iter().flatten().sum::<u64>().max(1);
+    let target = rng.range(41_000_000, 69_000_000) as u64;
+    for size in files.iter_mut().flatten() {
+        *size = (*size * target / raw_total).max(1);
+    }
+    let total = files.iter().flatten().sum::<u64>();
+    if total < target {
+        files[0].push(target - total);
+    }
+
+    let mut out = String::from("$ cd /\n");
+    // Each entry is a directory to list, or `None` to step back out to the parent:
+    let mut stack = vec![Some(0)];
+    while let Some(entry) = stack.pop() {
+        let Some(dir) = entry else {
+            out.push_str("$ cd ..\n");
+            continue;
+        };
+        if dir != 0 {
+            writeln!(out, "$ cd {}", dir_name(dir)).unwrap();
+        }
+        out.push_str("$ ls\n");
+        for child in &children[dir] {
+            writeln!(out, "dir {}", dir_name(*child)).unwrap();
+        }
+        for (i, size) in files[dir].iter().enumerate() {
+            writeln!(out, "{size} {}.{}", dir_name(dir), dir_name(i)).unwrap();
+        }
+        for child in children[dir].iter().rev() {
+            stack.push(None);
+            stack.push(Some(*child));
+        }
+    }
+    out
+}
+
+/// A unique lower-case name for the `n`th entry
+///
+/// The letters `d` and `l` are left out so a name can never be mistaken for `cd`, `ls` or `dir`.
+fn dir_name(mut n: usize) -> String {
+    const LETTERS: &[u8; 24] = b"abcefghijkmnopqrstuvwxyz";
+    let mut name = Vec::new();
+    loop {
+        name.push(LETTERS[n % LETTERS.len()]);
+        n /= LETTERS.len();
+        if n == 0 {
+            break;
+        }
+    }
+    String::from_utf8(name).unwrap()
+}
+
+/// A `size` by `size` forest of tree heights
+pub fn day_08(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::with_capacity(size * (size + 1));
+    for _ in 0..size {
+        for _ in 0..size {
+            out.push(char::from(b'0' + rng.range(0, 9) as u8));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// `size` rope motions
+pub fn day_09(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..size {
+        let direction = rng.choose(&['U', 'D', 'L', 'R']);
+        writeln!(out, "{direction} {}", rng.range(1, 20)).unwrap();
+    }
+    out
+}
+
+#[test]
+fn test_day_03_single_shared_item() {
+    use std::collections::HashSet;
+
+    let input = day_03(&mut Rng::new(3), 300);
+    let sacks = input
+        .lines()
+        .map(|l| l.chars().collect::<Vec<char>>())
+        .collect::<Vec<Vec<char>>>();
+    for sack in &sacks {
+        let (left, right) = sack.split_at(sack.len() / 2);
+        let left = left.iter().collect::<HashSet<_>>();
+        let right = right.iter().collect::<HashSet<_>>();
+        assert_eq!(left.intersection(&right).count(), 1);
+    }
+    for group in sacks.chunks(3) {
+        let common = group
+            .iter()
+            .map(|s| s.iter().copied().collect::<HashSet<char>>())
+            .reduce(|a, b| a.intersection(&b).copied().collect())
+            .unwrap();
+        assert_eq!(common.len(), 1);
+    }
+}
+
+#[test]
+fn test_day_06_marker_offset() {
+    for seed in 0..20 {
+        let signal = day_06(&mut Rng::new(seed), 200, 50 + seed as usize).unwrap();
+        let bytes = signal.as_bytes();
+        let first_marker = |n: usize| {
+            bytes
+                .windows(n)
+                .position(|w| (1..n).all(|i| !w[..i].contains(&w[i])))
+                .map(|p| p + n)
+        };
+        assert_eq!(first_marker(4), Some(54 + seed as usize));
+        assert_eq!(first_marker(14), Some(64 + seed as usize));
+    }
+}
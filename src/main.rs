@@ -1,16 +1,50 @@
-mod day_01;
-mod day_02;
-mod day_03;
-mod day_04;
-mod day_05;
-mod day_06;
-mod day_07;
-mod day_08;
-mod day_09;
-
-mod utils;
+mod cli;
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use aoc_2022_rust::{
+    day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09, differential, generate,
+};
+use cli::Flags;
 
 fn main() {
+    let mut args = std::env::args().skip(1);
+    let result = match args.next().as_deref() {
+        None => {
+            print_answers();
+            Ok(())
+        }
+        Some("generate") => Flags::parse(args).and_then(|flags| run_generate(&flags)),
+        Some("diff") => Flags::parse(args).and_then(|flags| run_differential(&flags)),
+        Some("top-elves") => Flags::parse(args).and_then(|flags| run_top_elves(&flags)),
+        Some("calorie-stats") => Flags::parse(args).and_then(|flags| run_calorie_stats(&flags)),
+        Some("teams") => Flags::parse(args).and_then(|flags| run_teams(&flags)),
+        Some("rps") => Flags::parse(args).and_then(|flags| run_rps(&flags)),
+        Some("rps-analysis") => Flags::parse(args).and_then(|flags| run_rps_analysis(&flags)),
+        Some("tournament") => Flags::parse(args).and_then(|flags| run_tournament(&flags)),
+        Some("rucksacks") => Flags::parse(args).and_then(|flags| run_rucksacks(&flags)),
+        Some("audit") => Flags::parse(args).and_then(|flags| run_audit(&flags)),
+        Some("find-groups") => Flags::parse(args).and_then(|flags| run_find_groups(&flags)),
+        Some("sections") => Flags::parse(args).and_then(|flags| run_sections(&flags)),
+        Some("coverage") => Flags::parse(args).and_then(|flags| run_coverage(&flags)),
+        Some("cleaning-groups") => Flags::parse(args).and_then(|flags| run_cleaning_groups(&flags)),
+        Some("gantt") => Flags::parse(args).and_then(|flags| run_gantt(&flags)),
+        Some("crane") => Flags::parse(args).and_then(|flags| run_crane(&flags)),
+        Some("bay") => Flags::parse(args).and_then(|flags| run_bay(&flags)),
+        Some("reconstruct") => Flags::parse(args).and_then(|flags| run_reconstruct(&flags)),
+        Some("interpret") => Flags::parse(args).and_then(|flags| run_interpret(&flags)),
+        Some(cmd) => Err(format!("unrecognized command: {cmd}")),
+    };
+    if let Err(e) = result {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn print_answers() {
     println!("Day 1 (Part 1): {answer}", answer = day_01::answer_part_1());
     println!("Day 1 (Part 2): {answer}", answer = day_01::answer_part_2());
     println!("Day 2 (Part 1): {answer}", answer = day_02::answer_part_1());
@@ -33,3 +67,402 @@ fn main() {
     println!("Day 9 (Part 1): {answer}", answer = day_09::answer_part_1());
     println!("Day 9 (Part 2): {answer}", answer = day_09::answer_part_2());
 }
+
+/// `generate --day N [--seed S] [--size K] [--offset O]`
+fn run_generate(flags: &Flags) -> Result<(), String> {
+    let day = flags.require("day")?;
+    let options = generate::Options {
+        seed: flags.get("seed")?.unwrap_or(0),
+        size: flags.get("size")?.unwrap_or(100),
+        offset: flags.get("offset")?,
+    };
+    print!("{}", generate::generate(day, &options)?);
+    Ok(())
+}
+
+/// `diff [--day N] [--cases C] [--seed S] [--size K]`
+fn run_differential(flags: &Flags) -> Result<(), String> {
+    let day = flags.get::<u8>("day")?;
+    let cases = flags.get("cases")?.unwrap_or(1_000);
+    let seed = flags.get("seed")?.unwrap_or(0);
+    let size = flags.get("size")?.unwrap_or(100);
+    for check in differential::CHECKS {
+        if day.is_some_and(|d| d != check.day) {
+            continue;
+        }
+        check.run(cases, seed, size).map_err(|d| d.to_string())?;
+        println!("{}: {cases} cases agree", check.name);
+    }
+    Ok(())
+}
+
+/// `top-elves [--k K] [--input PATH]`
+fn run_top_elves(flags: &Flags) -> Result<(), String> {
+    let k = flags.get("k")?.unwrap_or(3);
+    let input = input_reader(flags, 1)?;
+    for elf in day_01::top_elves(input, k)? {
+        println!("{elf}");
+    }
+    Ok(())
+}
+
+/// `calorie-stats [--bins N] [--input PATH]`
+fn run_calorie_stats(flags: &Flags) -> Result<(), String> {
+    let bins = flags.get("bins")?.unwrap_or(10);
+    let stats = day_01::CalorieStats::from_reader(input_reader(flags, 1)?)?;
+    print!("{stats}");
+    println!();
+    print!("{}", stats.histogram(bins, 50));
+    Ok(())
+}
+
+/// `teams --n N [--method auto|exact|greedy|kk] [--input PATH]`
+fn run_teams(flags: &Flags) -> Result<(), String> {
+    let n = flags.require("n")?;
+    let method = flags.get("method")?.unwrap_or(day_01::Method::Auto);
+    let totals = day_01::Elves::new(input_reader(flags, 1)?)
+        .map(|elf| elf.map(|e| e.total))
+        .collect::<Result<Vec<usize>, String>>()?;
+    println!("{}", day_01::partition(&totals, n, method)?);
+    Ok(())
+}
+
+/// `rps [--game rps|rpsls|rps7|rps15] [--input PATH]`
+fn run_rps(flags: &Flags) -> Result<(), String> {
+    let game = day_02::Game::by_name(&flags.get::<String>("game")?.unwrap_or("rps".into()))?;
+    for (label, strategy) in [
+        ("weapon", day_02::Strategy::Weapon),
+        ("outcome", day_02::Strategy::Outcome),
+    ] {
+        let score = day_02::score_guide(input_reader(flags, 2)?, &game, strategy)?;
+        println!("second column as {label}: {score}");
+    }
+    Ok(())
+}
+
+/// `rps-analysis [--input PATH]`
+fn run_rps_analysis(flags: &Flags) -> Result<(), String> {
+    print!(
+        "{}",
+        day_02::Analysis::from_reader(input_reader(flags, 2)?)?
+    );
+    Ok(())
+}
+
+/// `interpret [--config PATH] [--game rps|rpsls|rps7|rps15] [--input PATH]`
+///
+/// Scores the guide under every interpretation in the config file, if given, followed by every
+/// way of assigning the opponent's column to weapons and the second column to weapons or
+/// outcomes in the game.
+fn run_interpret(flags: &Flags) -> Result<(), String> {
+    let mut interpretations = match flags.get::<String>("config")? {
+        Some(path) => day_02::parse_interpretations(
+            &std::fs::read_to_string(&path).map_err(|e| format!("failed to read {path}: {e}"))?,
+        )?,
+        None => Vec::new(),
+    };
+    let game = day_02::Game::by_name(&flags.get::<String>("game")?.unwrap_or("rps".into()))?;
+    interpretations.extend(day_02::Interpretation::all(&game));
+    let guide = std::io::read_to_string(input_reader(flags, 2)?)
+        .map_err(|e| format!("failed to read guide: {e}"))?;
+    for interpretation in interpretations {
+        let score = interpretation.score_guide(guide.as_bytes())?;
+        println!("{}: {score}", interpretation.name);
+    }
+    Ok(())
+}
+
+/// `tournament --guides PATH,PATH,... [--format round-robin|elimination]`
+fn run_tournament(flags: &Flags) -> Result<(), String> {
+    let players = flags
+        .require::<String>("guides")?
+        .split(',')
+        .map(|path| {
+            let file = File::open(path).map_err(|e| format!("failed to open {path}: {e}"))?;
+            let name = std::path::Path::new(path)
+                .file_stem()
+                .map_or(path.into(), |stem| stem.to_string_lossy());
+            day_02::Player::from_reader(&name, BufReader::new(file))
+        })
+        .collect::<Result<Vec<day_02::Player>, String>>()?;
+    match flags.get::<String>("format")?.as_deref() {
+        None | Some("round-robin") => print!("{}", day_02::round_robin(&players)),
+        Some("elimination") => print!("{}", day_02::elimination(&players)?),
+        Some(format) => return Err(format!("unknown tournament format: {format}")),
+    }
+    Ok(())
+}
+
+/// `rucksacks [--compartments K] [--group-size N] [--input PATH]`
+fn run_rucksacks(flags: &Flags) -> Result<(), String> {
+    let layout = day_03::Layout::new(
+        flags.get("compartments")?.unwrap_or(2),
+        flags.get("group-size")?.unwrap_or(3),
+    )?;
+    let misplaced = layout.sum_misplaced(input_reader(flags, 3)?)?;
+    println!("misplaced items: {misplaced}");
+    let badges = layout.sum_badges(input_reader(flags, 3)?)?;
+    println!("badges: {badges}");
+    Ok(())
+}
+
+/// `audit [--format text|json|csv] [--compartments K] [--group-size N] [--input PATH]`
+fn run_audit(flags: &Flags) -> Result<(), String> {
+    let layout = day_03::Layout::new(
+        flags.get("compartments")?.unwrap_or(2),
+        flags.get("group-size")?.unwrap_or(3),
+    )?;
+    let audit = layout.audit(input_reader(flags, 3)?)?;
+    match flags.get::<String>("format")?.as_deref() {
+        None | Some("text") => print!("{audit}"),
+        Some("json") => print!("{}", audit.to_json()),
+        Some("csv") => print!("{}", audit.to_csv()),
+        Some(format) => return Err(format!("unknown audit format: {format}")),
+    }
+    Ok(())
+}
+
+/// `find-groups [--group-size N] [--input PATH]`
+///
+/// Groups the rucksacks without regard to the order of the lines, printing the line numbers in
+/// each group.
+fn run_find_groups(flags: &Flags) -> Result<(), String> {
+    let layout = day_03::Layout::new(2, flags.get("group-size")?.unwrap_or(3))?;
+    let sacks = std::io::read_to_string(input_reader(flags, 3)?)
+        .map_err(|e| format!("failed to read rucksacks: {e}"))?;
+    let sacks = sacks.lines().collect::<Vec<&str>>();
+    let groups = layout
+        .find_groups(&sacks)?
+        .ok_or("the rucksacks can't be split into groups with one badge each")?;
+    for group in groups {
+        let lines = group
+            .iter()
+            .map(|i| (i + 1).to_string())
+            .collect::<Vec<String>>();
+        let members = group.iter().map(|i| sacks[*i]).collect::<Vec<&str>>();
+        println!(
+            "lines {}: badge priority {}",
+            lines.join(", "),
+            layout.badge(&members)?
+        );
+    }
+    Ok(())
+}
+
+/// `sections [--input PATH]`
+fn run_sections(flags: &Flags) -> Result<(), String> {
+    let assignments = day_04::read_assignments(input_reader(flags, 4)?)?
+        .into_iter()
+        .map(|a| a.range)
+        .collect::<Vec<day_04::SectionRange>>();
+    let covered = assignments.iter().copied().collect::<day_04::SectionSet>();
+    println!("covered: {} sections in {covered}", covered.len());
+    let coverage = day_04::Coverage::new(&assignments);
+    for (label, set) in [
+        ("nobody", coverage.exactly(0)),
+        ("exactly one elf", coverage.exactly(1)),
+        ("exactly two elves", coverage.exactly(2)),
+        ("more than two elves", coverage.more_than(2)),
+    ] {
+        println!("covered by {label}: {} sections in {set}", set.len());
+    }
+    Ok(())
+}
+
+/// `coverage [--section S | --range A-B] [--input PATH]`
+///
+/// Lists the assignments covering a section or overlapping a range, or else prints how many
+/// elves cover each run of sections.
+fn run_coverage(flags: &Flags) -> Result<(), String> {
+    let index = day_04::AssignmentIndex::new(day_04::read_assignments(input_reader(flags, 4)?)?);
+    let found = match (
+        flags.get("section")?,
+        flags.get::<day_04::SectionRange>("range")?,
+    ) {
+        (Some(section), None) => index.covering(section),
+        (None, Some(range)) => index.overlapping(&range),
+        (None, None) => {
+            for (range, depth) in index.depth_profile() {
+                println!("{}-{}: {depth}", range.start(), range.end());
+            }
+            return Ok(());
+        }
+        (Some(_), Some(_)) => return Err("give either --section or --range, not both".into()),
+    };
+    for assignment in found {
+        println!("{assignment}");
+    }
+    Ok(())
+}
+
+/// `cleaning-groups [--input PATH]`
+///
+/// Lines may list any number of assignments. Elves are numbered from one within their line.
+fn run_cleaning_groups(flags: &Flags) -> Result<(), String> {
+    let numbers = |elves: Vec<usize>| {
+        let elves = elves
+            .iter()
+            .map(|i| (i + 1).to_string())
+            .collect::<Vec<String>>();
+        format!("[{}]", elves.join(", "))
+    };
+    for (n, line) in input_reader(flags, 4)?.lines().enumerate() {
+        let line = line.map_err(|e| format!("failed to read line: {e}"))?;
+        let group = line
+            .parse::<day_04::AssignmentGroup>()
+            .map_err(|e| format!("line {}: {e}", n + 1))?;
+        println!(
+            "line {}: redundant {}, minimal cover {}",
+            n + 1,
+            numbers(group.redundant()),
+            numbers(group.minimal_cover())
+        );
+    }
+    Ok(())
+}
+
+/// `gantt [--lines A-B] [--width W] [--input PATH]`
+fn run_gantt(flags: &Flags) -> Result<(), String> {
+    let (first, last) = match flags.get::<String>("lines")? {
+        Some(lines) => {
+            let (first, last) = lines.split_once('-').unwrap_or((&lines, &lines));
+            let number = |n: &str| {
+                n.parse::<usize>()
+                    .map_err(|e| format!("invalid line range {lines}: {e}"))
+            };
+            (number(first)?, number(last)?)
+        }
+        None => (1, usize::MAX),
+    };
+    let mut lines = Vec::new();
+    for (n, line) in input_reader(flags, 4)?.lines().enumerate() {
+        let n = n + 1;
+        if n > last {
+            break;
+        }
+        let line = line.map_err(|e| format!("failed to read line: {e}"))?;
+        if n >= first {
+            let group = line
+                .parse::<day_04::AssignmentGroup>()
+                .map_err(|e| format!("line {n}: {e}"))?;
+            lines.push((n, group));
+        }
+    }
+    print!(
+        "{}",
+        day_04::gantt(&lines, flags.get("width")?.unwrap_or(100))
+    );
+    Ok(())
+}
+
+/// `crane [CRANE OPTIONS] [--after N] [--delay MS] [--input PATH]`
+///
+/// Draws the cargo bay after each instruction in turn, or only after the first `N` with
+/// `--after`. With `--delay`, each frame replaces the last in the terminal, with the current
+/// instruction highlighted.
+fn run_crane(flags: &Flags) -> Result<(), String> {
+    let mut cargo_bay = cargo_bay(flags)?;
+    let instructions = cargo_bay.load(input_reader(flags, 5)?)?;
+    let total = instructions.len();
+    let after = flags.get::<usize>("after")?;
+    let delay = flags
+        .get::<u64>("delay")?
+        .map(std::time::Duration::from_millis);
+    if after.is_none() {
+        println!("start:\n{cargo_bay}");
+    }
+    for (step, instruction) in instructions
+        .into_iter()
+        .enumerate()
+        .take(after.unwrap_or(total))
+    {
+        let heading = format!("step {}/{total}: {instruction}", step + 1);
+        cargo_bay.move_crate(instruction)?;
+        match (after, delay) {
+            (Some(_), _) => {}
+            (None, Some(delay)) => {
+                // Clear the screen, and show the instruction in reverse video:
+                print!("\x1b[2J\x1b[H\x1b[7m{heading}\x1b[0m\n{cargo_bay}");
+                std::io::Write::flush(&mut std::io::stdout()).map_err(|e| e.to_string())?;
+                std::thread::sleep(delay);
+            }
+            (None, None) => println!("> {heading}\n{cargo_bay}"),
+        }
+    }
+    if let Some(after) = after {
+        println!(
+            "after {} of {total} instructions:\n{cargo_bay}",
+            after.min(total)
+        );
+    }
+    Ok(())
+}
+
+/// `bay [CRANE OPTIONS] [--after N] [--locate CRATE] [--input PATH]`
+///
+/// Prints the top crate and contents, from the bottom up, of every stack after the first `N`
+/// instructions, or all of them, and where any crates with the given label are.
+fn run_bay(flags: &Flags) -> Result<(), String> {
+    let mut cargo_bay = cargo_bay(flags)?;
+    let instructions = cargo_bay.load(input_reader(flags, 5)?)?;
+    let after = flags.get("after")?.unwrap_or(instructions.len());
+    for instruction in instructions.into_iter().take(after) {
+        cargo_bay.move_crate(instruction)?;
+    }
+    println!("top crates: {:?}", cargo_bay.top_crates());
+    for (slot, height) in cargo_bay.heights() {
+        let contents = cargo_bay.contents(slot).unwrap_or_default();
+        println!("stack {slot} ({height} crates): {contents}");
+    }
+    if let Some(label) = flags.get::<char>("locate")? {
+        for (slot, height) in cargo_bay.locate(label) {
+            println!("{label} is in stack {slot} at height {height}");
+        }
+    }
+    println!("energy spent: {}", cargo_bay.energy());
+    Ok(())
+}
+
+/// An empty cargo bay with the crane chosen by
+/// `[--model 9000|9001|bottom|limited:N] [--cost-per-move E] [--cost-per-crate E]`
+fn cargo_bay(flags: &Flags) -> Result<day_05::CargoBay, String> {
+    let mut crane = day_05::crane_by_name(&flags.get::<String>("model")?.unwrap_or("9000".into()))?;
+    let per_move = flags.get("cost-per-move")?;
+    let per_crate = flags.get("cost-per-crate")?;
+    if per_move.is_some() || per_crate.is_some() {
+        crane = Box::new(day_05::MeteredCrane {
+            crane,
+            per_move: per_move.unwrap_or(0),
+            per_crate: per_crate.unwrap_or(0),
+        });
+    }
+    Ok(day_05::CargoBay::with_crane(crane))
+}
+
+/// `reconstruct [--model 9000|9001|bottom|limited:N] [--input PATH]`
+///
+/// The input's drawing shows the stacks at the end, and the starting drawing is printed for the
+/// given crane, or for each of the 9000 and 9001.
+fn run_reconstruct(flags: &Flags) -> Result<(), String> {
+    let input = std::io::read_to_string(input_reader(flags, 5)?)
+        .map_err(|e| format!("failed to read input: {e}"))?;
+    let models = match flags.get::<String>("model")? {
+        Some(model) => vec![model],
+        None => vec!["9000".to_string(), "9001".to_string()],
+    };
+    for model in models {
+        let start = day_05::reconstruct(input.as_bytes(), &model)?;
+        println!("start for crane {model}:\n{start}");
+    }
+    Ok(())
+}
+
+/// Open the file given by `--input`, or the real input for the `day`
+fn input_reader(flags: &Flags, day: u8) -> Result<BufReader<File>, String> {
+    let path = flags
+        .get::<String>("input")?
+        .unwrap_or_else(|| format!("input/day_{day:02}.txt"));
+    File::open(&path)
+        .map(BufReader::new)
+        .map_err(|e| format!("failed to open {path}: {e}"))
+}